@@ -2,7 +2,7 @@ use anyhow::*;
 use futures::prelude::*;
 use tokio::{time::{self, Duration}};
 use repulse::{
-    Client,
+    Client, Proplist,
     tag_struct::{SampleSpec, ChannelMap}, sample::SampleFormat,
 };
 use std::mem::size_of;
@@ -39,7 +39,7 @@ async fn main() -> Result<()> {
 
     println!("PCM length: {}", audio.len());
 
-    let client = Client::connect().await
+    let client = Client::connect("repulse play", Proplist::new()).await
         .context("Failed to create client")?;
     
     let bit_depth = size_of::<i16>();
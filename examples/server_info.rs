@@ -1,9 +1,9 @@
 use anyhow::*;
-use repulse::Client;
+use repulse::{Client, Proplist};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let client = Client::connect().await
+    let client = Client::connect("repulse server_info", Proplist::new()).await
         .context("Failed to create client")?;
 
     let server_info = client.get_server_info().await?;
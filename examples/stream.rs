@@ -5,7 +5,7 @@ use lewton::{
 };
 use futures::{TryStreamExt, StreamExt};
 use ogg::reading::async_api::PacketReader;
-use repulse::{sample::SampleFormat, tag_struct::{ChannelMap, SampleSpec}};
+use repulse::{sample::SampleFormat, tag_struct::{ChannelMap, SampleSpec}, Proplist};
 use std::mem::size_of;
 use tokio::{io, time};
 use time::Duration;
@@ -33,7 +33,7 @@ async fn play_rainwave_stream() -> Result<()> {
 
     let mut packet_reader = PacketReader::new(stream);
 
-    let pulseaudio = repulse::Client::connect().await?;
+    let pulseaudio = repulse::Client::connect("repulse stream", Proplist::new()).await?;
 
     let headers = read_headers(&mut packet_reader).await?;
     let info = &headers.0;
@@ -1,33 +1,98 @@
 use anyhow::*;
-use tokio::fs;
-use tokio::net::UnixStream;
-use futures::channel::oneshot;
-use crate::{command, PROTOCOL_VERSION, tag_struct, broker::{SendFrame, start_broker}, frame::Frame, stream::PlaybackStream, VOLUME_NORMAL};
-use command::{ServerInfo, Command, AuthReply, CommandHeader, Tag, CommandKind, CreatePlaybackStream, CreatePlaybackStreamReply, SinkRef};
-use std::{collections::{btree_map, BTreeMap}, sync::Arc, mem};
-use tokio::sync::Mutex;
+use tokio::net::{self as net, UnixStream, TcpStream};
+use tokio::io::{AsyncRead, AsyncWrite};
+use futures::{Stream, channel::{oneshot, mpsc}};
+use crate::{command, PROTOCOL_VERSION, MIN_PROTOCOL_VERSION, tag_struct, broker::{SendFrame, start_broker}, frame::Frame, stream::PlaybackStream, VOLUME_NORMAL, VOLUME_INVALID, ServerAddress, server_address::AddressFamily, config::ClientConfig, cookie::Cookie, error::ErrorKind, proplist::{Proplist, keys}, format::FormatInfo};
+use command::{ServerInfo, Command, AuthReply, SetClientNameReply, CommandHeader, Tag, CommandKind, CreatePlaybackStream, CreatePlaybackStreamReply, SinkRef, SinkInfo, SourceRef, SourceInfo, SinkInputInfo, SourceOutputInfo, ClientInfo, ModuleInfo, CardRef, CardInfo, SampleRef, SampleInfo, CreateUploadStreamReply, PlaySampleReply, LookupSinkReply, LookupSourceReply, LoadModuleReply, SubscriptionMask, SubscriptionEvent, ServerEvent, StreamEvent, StreamDirection};
+use std::{collections::{btree_map, BTreeMap}, sync::Arc, mem, env, process, convert::TryFrom, pin::Pin, task::{self, Poll}};
+use tokio::{runtime, sync::Mutex};
 use tag_struct::{SampleSpec, TagStruct, ChannelMap, ChannelVolume};
 
+/// Size of the data frames a sample is uploaded in
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
 #[derive(Clone)]
 pub struct Client {
     inner: Arc<Mutex<InnerClient>>,
+    config: Arc<ClientConfig>,
+    /// Held while updating the server's subscription mask, so updates can't overtake each other
+    subscribe_lock: Arc<Mutex<()>>,
 }
 
 impl Client {
-    pub async fn connect() -> Result<Self> {
-        let cookie = load_cookie().await
-            .context("Loading cookie failed")?;
-        let conn = UnixStream::connect("/run/user/1000/pulse/native").await
-            .context("Connecting to server failed")?;
-
-        let inner = InnerClient {
-            send_frame: Box::new(|_| panic!()), // TODO: Get rid of this
-            next_tag: 0,
-            reply_senders: BTreeMap::new(),
-            sync_id: 0,
-        };
+    /// Connects using the configuration from `ClientConfig::load`.
+    ///
+    /// The client is registered under `application_name`, together with the given properties
+    /// (e.g. `application.id` or `application.icon_name`) and details about the current process.
+    pub async fn connect(application_name: &str, proplist: Proplist) -> Result<Self> {
+        let config = ClientConfig::load().await
+            .context("Loading client configuration failed")?;
+
+        Self::connect_with(config, application_name, proplist).await
+    }
+
+    /// Connects to the first reachable server out of `ServerAddress::candidates`.
+    pub async fn connect_with(config: ClientConfig, application_name: &str, proplist: Proplist) -> Result<Self> {
+        let config = Arc::new(config);
+        let proplist = client_proplist(application_name, proplist);
+        let mut failures = Vec::new();
+
+        for address in ServerAddress::candidates(&config) {
+            let result = match address {
+                Ok(address) => Self::connect_address(address, config.clone(), proplist.clone()).await,
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(client) => return Ok(client),
+                Err(err) => failures.push(format!("{:#}", err)),
+            }
+        }
+
+        ensure!(!failures.is_empty(), "No server address candidates");
+
+        bail!("Connecting to server failed: {}", failures.join("; "))
+    }
+
+    /// Connects to the given server, ignoring any configured server addresses.
+    pub async fn connect_to(address: ServerAddress, application_name: &str, proplist: Proplist) -> Result<Self> {
+        let config = ClientConfig::load().await
+            .context("Loading client configuration failed")?;
+        let proplist = client_proplist(application_name, proplist);
+
+        Self::connect_address(address, Arc::new(config), proplist).await
+    }
+
+    async fn connect_address(address: ServerAddress, config: Arc<ClientConfig>, proplist: Proplist) -> Result<Self> {
+        let cookie = Cookie::load(&config).await;
+
+        match address {
+            ServerAddress::Unix(path) => {
+                let conn = UnixStream::connect(&path).await
+                    .with_context(|| format!("Connecting to {} failed", path.display()))?;
+
+                Self::from_stream(conn, cookie, config, proplist).await
+            },
+            ServerAddress::Tcp { host, port, family } => {
+                let conn = connect_tcp(&host, port, family).await
+                    .with_context(|| format!("Connecting to {}:{} failed", host, port))?;
+
+                Self::from_stream(conn, cookie, config, proplist).await
+            },
+        }
+    }
+
+    async fn from_stream<S>(conn: S, cookie: Cookie, config: Arc<ClientConfig>, proplist: Proplist) -> Result<Self>
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let inner = InnerClient::new(Box::new(|_| panic!())); // TODO: Get rid of this
         let inner = Arc::new(Mutex::new(inner));
-        let client = Self { inner };
+        let client = Self {
+            inner,
+            config,
+            subscribe_lock: Arc::new(Mutex::new(())),
+        };
 
         let (send_frame, abort_handle) = {
             let client = client.clone();
@@ -44,25 +109,469 @@ impl Client {
         // TODO: Get rid of this
         client.inner.lock().await.send_frame = send_frame;
 
-        client.send_command::<_, AuthReply>(command::Auth {
+        let auth_reply = client.send_command::<_, AuthReply>(command::Auth {
             protocol_version: PROTOCOL_VERSION,
             cookie,
         }).await?;
 
+        ensure!(
+            auth_reply.protocol_version >= MIN_PROTOCOL_VERSION,
+            "Server protocol version {} is too old, need at least {}",
+            auth_reply.protocol_version, MIN_PROTOCOL_VERSION,
+        );
+
+        client.inner.lock().await.protocol_version = PROTOCOL_VERSION.min(auth_reply.protocol_version);
+
+        let reply = client.send_command::<_, SetClientNameReply>(command::SetClientName {
+            proplist,
+        }).await
+            .context("Setting client name failed")?;
+
+        client.inner.lock().await.client_index = reply.client_index;
+
         Ok(client)
     }
 
+    /// Index of this client on the server. Only known since protocol v13.
+    pub async fn client_index(&self) -> Option<u32> {
+        self.inner.lock().await.client_index
+    }
+
+    /// The protocol version negotiated with the server
+    pub async fn protocol_version(&self) -> u32 {
+        self.inner.lock().await.protocol_version
+    }
+
     pub async fn get_server_info(&self) -> Result<ServerInfo> {
         self.send_command::<_, ServerInfo>(command::GetServerInfo).await
     }
 
+    pub async fn get_sink_info(&self, sink_ref: SinkRef) -> Result<SinkInfo> {
+        self.send_command::<_, SinkInfo>(command::GetSinkInfo { sink_ref }).await
+    }
+
+    pub async fn list_sinks(&self) -> Result<Vec<SinkInfo>> {
+        self.send_command::<_, Vec<SinkInfo>>(command::GetSinkInfoList).await
+    }
+
+    pub async fn get_source_info(&self, source_ref: SourceRef) -> Result<SourceInfo> {
+        self.send_command::<_, SourceInfo>(command::GetSourceInfo { source_ref }).await
+    }
+
+    pub async fn list_sources(&self) -> Result<Vec<SourceInfo>> {
+        self.send_command::<_, Vec<SourceInfo>>(command::GetSourceInfoList).await
+    }
+
+    pub async fn get_sink_input_info(&self, index: u32) -> Result<SinkInputInfo> {
+        self.send_command::<_, SinkInputInfo>(command::GetSinkInputInfo { index }).await
+    }
+
+    pub async fn list_sink_inputs(&self) -> Result<Vec<SinkInputInfo>> {
+        self.send_command::<_, Vec<SinkInputInfo>>(command::GetSinkInputInfoList).await
+    }
+
+    pub async fn get_source_output_info(&self, index: u32) -> Result<SourceOutputInfo> {
+        self.send_command::<_, SourceOutputInfo>(command::GetSourceOutputInfo { index }).await
+    }
+
+    pub async fn list_source_outputs(&self) -> Result<Vec<SourceOutputInfo>> {
+        self.send_command::<_, Vec<SourceOutputInfo>>(command::GetSourceOutputInfoList).await
+    }
+
+    pub async fn get_client_info(&self, index: u32) -> Result<ClientInfo> {
+        self.send_command::<_, ClientInfo>(command::GetClientInfo { index }).await
+    }
+
+    pub async fn list_clients(&self) -> Result<Vec<ClientInfo>> {
+        self.send_command::<_, Vec<ClientInfo>>(command::GetClientInfoList).await
+    }
+
+    pub async fn get_module_info(&self, index: u32) -> Result<ModuleInfo> {
+        self.send_command::<_, ModuleInfo>(command::GetModuleInfo { index }).await
+    }
+
+    pub async fn list_modules(&self) -> Result<Vec<ModuleInfo>> {
+        self.send_command::<_, Vec<ModuleInfo>>(command::GetModuleInfoList).await
+    }
+
+    /// Loads a module, e.g. `module-null-sink`, and returns its index.
+    ///
+    /// `args` can be a plain string or a `ModuleArguments`.
+    pub async fn load_module(&self, name: impl Into<String>, args: impl Into<String>) -> Result<u32> {
+        let reply = self.send_command::<_, LoadModuleReply>(command::LoadModule {
+            name: name.into(),
+            argument: args.into(),
+        }).await?;
+
+        Ok(reply.index)
+    }
+
+    pub async fn unload_module(&self, index: u32) -> Result<()> {
+        self.send_command::<_, ()>(command::UnloadModule { index }).await
+    }
+
+    /// Requires protocol v15
+    pub async fn get_card_info(&self, card_ref: CardRef) -> Result<CardInfo> {
+        self.ensure_protocol_version(15, "Card introspection").await?;
+
+        self.send_command::<_, CardInfo>(command::GetCardInfo { card_ref }).await
+    }
+
+    /// Requires protocol v15
+    pub async fn list_cards(&self) -> Result<Vec<CardInfo>> {
+        self.ensure_protocol_version(15, "Card introspection").await?;
+
+        self.send_command::<_, Vec<CardInfo>>(command::GetCardInfoList).await
+    }
+
+    /// Switches the card to another profile, e.g. from `a2dp-sink` to `headset-head-unit`.
+    /// Requires protocol v15.
+    pub async fn set_card_profile(&self, card_ref: CardRef, profile_name: impl Into<String>) -> Result<()> {
+        self.ensure_protocol_version(15, "Setting card profiles").await?;
+
+        self.send_command::<_, ()>(command::SetCardProfile {
+            card_ref,
+            profile: profile_name.into(),
+        }).await
+    }
+
+    /// Makes the named sink the server's default sink
+    pub async fn set_default_sink(&self, name: impl Into<String>) -> Result<()> {
+        self.send_command::<_, ()>(command::SetDefaultSink { name: name.into() }).await
+    }
+
+    /// Makes the named source the server's default source
+    pub async fn set_default_source(&self, name: impl Into<String>) -> Result<()> {
+        self.send_command::<_, ()>(command::SetDefaultSource { name: name.into() }).await
+    }
+
+    /// Returns the index of the named sink. Accepts `@DEFAULT_SINK@`.
+    pub async fn lookup_sink(&self, name: impl Into<String>) -> Result<u32> {
+        let reply = self.send_command::<_, LookupSinkReply>(command::LookupSink { name: name.into() }).await?;
+
+        Ok(reply.index)
+    }
+
+    /// Returns the index of the named source. Accepts `@DEFAULT_SOURCE@` and `@DEFAULT_MONITOR@`.
+    pub async fn lookup_source(&self, name: impl Into<String>) -> Result<u32> {
+        let reply = self.send_command::<_, LookupSourceReply>(command::LookupSource { name: name.into() }).await?;
+
+        Ok(reply.index)
+    }
+
+    /// Moves a sink input to another sink. Requires protocol v10.
+    pub async fn move_sink_input(&self, index: u32, sink_ref: SinkRef) -> Result<()> {
+        self.ensure_protocol_version(10, "Moving sink inputs").await?;
+
+        self.send_command::<_, ()>(command::MoveSinkInput { index, sink_ref }).await
+    }
+
+    /// Moves a source output to another source. Requires protocol v10.
+    pub async fn move_source_output(&self, index: u32, source_ref: SourceRef) -> Result<()> {
+        self.ensure_protocol_version(10, "Moving source outputs").await?;
+
+        self.send_command::<_, ()>(command::MoveSourceOutput { index, source_ref }).await
+    }
+
+    /// Disconnects a client. Fails with `ErrorKind::NoEntity` if there is no such client.
+    pub async fn kill_client(&self, index: u32) -> Result<()> {
+        self.send_command::<_, ()>(command::KillClient { index }).await
+    }
+
+    /// Fails with `ErrorKind::NoEntity` if there is no such sink input
+    pub async fn kill_sink_input(&self, index: u32) -> Result<()> {
+        self.send_command::<_, ()>(command::KillSinkInput { index }).await
+    }
+
+    /// Fails with `ErrorKind::NoEntity` if there is no such source output
+    pub async fn kill_source_output(&self, index: u32) -> Result<()> {
+        self.send_command::<_, ()>(command::KillSourceOutput { index }).await
+    }
+
+    /// Suspends or resumes a sink, closing or reopening the underlying device. Requires protocol v11.
+    pub async fn suspend_sink(&self, sink_ref: SinkRef, suspend: bool) -> Result<()> {
+        self.ensure_protocol_version(11, "Suspending sinks").await?;
+
+        self.send_command::<_, ()>(command::SuspendSink { sink_ref: Some(sink_ref), suspend }).await
+    }
+
+    /// Requires protocol v11
+    pub async fn suspend_all_sinks(&self, suspend: bool) -> Result<()> {
+        self.ensure_protocol_version(11, "Suspending sinks").await?;
+
+        self.send_command::<_, ()>(command::SuspendSink { sink_ref: None, suspend }).await
+    }
+
+    /// Suspends or resumes a source, closing or reopening the underlying device. Requires protocol v11.
+    pub async fn suspend_source(&self, source_ref: SourceRef, suspend: bool) -> Result<()> {
+        self.ensure_protocol_version(11, "Suspending sources").await?;
+
+        self.send_command::<_, ()>(command::SuspendSource { source_ref: Some(source_ref), suspend }).await
+    }
+
+    /// Requires protocol v11
+    pub async fn suspend_all_sources(&self, suspend: bool) -> Result<()> {
+        self.ensure_protocol_version(11, "Suspending sources").await?;
+
+        self.send_command::<_, ()>(command::SuspendSource { source_ref: None, suspend }).await
+    }
+
+    /// Switches the active port of a sink, e.g. between speakers and headphones.
+    /// Requires protocol v16.
+    pub async fn set_sink_port(&self, sink_ref: SinkRef, port: impl Into<String>) -> Result<()> {
+        self.ensure_protocol_version(16, "Setting sink ports").await?;
+
+        self.send_command::<_, ()>(command::SetSinkPort { sink_ref, port: port.into() }).await
+    }
+
+    /// Switches the active port of a source. Requires protocol v16.
+    pub async fn set_source_port(&self, source_ref: SourceRef, port: impl Into<String>) -> Result<()> {
+        self.ensure_protocol_version(16, "Setting source ports").await?;
+
+        self.send_command::<_, ()>(command::SetSourcePort { source_ref, port: port.into() }).await
+    }
+
+    /// Sets the latency added to everything played or recorded through a card's port,
+    /// e.g. to compensate for Bluetooth delays. Requires protocol v27.
+    pub async fn set_port_latency_offset(&self, card_ref: CardRef, port: impl Into<String>, offset_usec: i64) -> Result<()> {
+        self.ensure_protocol_version(27, "Setting port latency offsets").await?;
+
+        self.send_command::<_, ()>(command::SetPortLatencyOffset {
+            card_ref,
+            port: port.into(),
+            offset: offset_usec,
+        }).await
+    }
+
+    pub async fn set_sink_volume(&self, sink_ref: SinkRef, volume: ChannelVolume) -> Result<()> {
+        self.send_command::<_, ()>(command::SetSinkVolume { sink_ref, volume }).await
+    }
+
+    /// Sets all channels of the sink to `volume`
+    pub async fn set_sink_volume_uniform(&self, sink_ref: SinkRef, volume: u32) -> Result<()> {
+        let channels = self.get_sink_info(sink_ref.clone()).await?.channel_map.positions.len();
+
+        self.set_sink_volume(sink_ref, ChannelVolume::uniform(channels, volume)).await
+    }
+
+    pub async fn set_sink_mute(&self, sink_ref: SinkRef, mute: bool) -> Result<()> {
+        self.send_command::<_, ()>(command::SetSinkMute { sink_ref, mute }).await
+    }
+
+    pub async fn set_source_volume(&self, source_ref: SourceRef, volume: ChannelVolume) -> Result<()> {
+        self.send_command::<_, ()>(command::SetSourceVolume { source_ref, volume }).await
+    }
+
+    /// Sets all channels of the source to `volume`
+    pub async fn set_source_volume_uniform(&self, source_ref: SourceRef, volume: u32) -> Result<()> {
+        let channels = self.get_source_info(source_ref.clone()).await?.channel_map.positions.len();
+
+        self.set_source_volume(source_ref, ChannelVolume::uniform(channels, volume)).await
+    }
+
+    pub async fn set_source_mute(&self, source_ref: SourceRef, mute: bool) -> Result<()> {
+        self.send_command::<_, ()>(command::SetSourceMute { source_ref, mute }).await
+    }
+
+    pub async fn set_sink_input_volume(&self, index: u32, volume: ChannelVolume) -> Result<()> {
+        self.send_command::<_, ()>(command::SetSinkInputVolume { index, volume }).await
+    }
+
+    /// Sets all channels of the sink input to `volume`
+    pub async fn set_sink_input_volume_uniform(&self, index: u32, volume: u32) -> Result<()> {
+        let channels = self.get_sink_input_info(index).await?.channel_map.positions.len();
+
+        self.set_sink_input_volume(index, ChannelVolume::uniform(channels, volume)).await
+    }
+
+    /// Requires protocol v11
+    pub async fn set_sink_input_mute(&self, index: u32, mute: bool) -> Result<()> {
+        self.ensure_protocol_version(11, "Muting sink inputs").await?;
+
+        self.send_command::<_, ()>(command::SetSinkInputMute { index, mute }).await
+    }
+
+    /// Requires protocol v22
+    pub async fn set_source_output_volume(&self, index: u32, volume: ChannelVolume) -> Result<()> {
+        self.ensure_protocol_version(22, "Setting source output volumes").await?;
+
+        self.send_command::<_, ()>(command::SetSourceOutputVolume { index, volume }).await
+    }
+
+    /// Sets all channels of the source output to `volume`. Requires protocol v22.
+    pub async fn set_source_output_volume_uniform(&self, index: u32, volume: u32) -> Result<()> {
+        let channels = self.get_source_output_info(index).await?.channel_map.positions.len();
+
+        self.set_source_output_volume(index, ChannelVolume::uniform(channels, volume)).await
+    }
+
+    /// Requires protocol v22
+    pub async fn set_source_output_mute(&self, index: u32, mute: bool) -> Result<()> {
+        self.ensure_protocol_version(22, "Muting source outputs").await?;
+
+        self.send_command::<_, ()>(command::SetSourceOutputMute { index, mute }).await
+    }
+
+    /// Uploads `data` to the sample cache under `name`, replacing any sample of the same name.
+    ///
+    /// The data has to be in the format given by `sample_spec`.
+    pub async fn upload_sample(
+        &self,
+        name: impl Into<String>,
+        sample_spec: SampleSpec,
+        channel_map: ChannelMap,
+        data: &[u8],
+        proplist: Proplist,
+    ) -> Result<()> {
+        let length = u32::try_from(data.len())
+            .context("Sample length exceeds 32 bits")?;
+        ensure!(length > 0, "Sample is empty");
+
+        let reply = self.send_command::<_, CreateUploadStreamReply>(command::CreateUploadStream {
+            name: name.into(),
+            sample_spec,
+            channel_map,
+            length,
+            proplist,
+        }).await?;
+
+        let channel = reply.channel;
+
+        for chunk in data.chunks(UPLOAD_CHUNK_SIZE) {
+            let frame = Frame {
+                channel,
+                offset_hi: 0,
+                offset_low: 0,
+                flags: 0,
+                data: chunk.into(),
+            };
+
+            if let Err(err) = self.send_frame(frame).await {
+                let _ = self.send_command::<_, ()>(command::DeleteUploadStream { channel }).await;
+
+                return Err(err).context("Failed to send sample data");
+            }
+        }
+
+        self.send_command::<_, ()>(command::FinishUploadStream { channel }).await
+    }
+
+    /// Plays a sample from the sample cache.
+    ///
+    /// Without a `volume` the sample's default volume is used.
+    /// Returns the index of the created sink input, if the server reports it (v13+).
+    pub async fn play_sample(
+        &self,
+        sample_name: impl Into<String>,
+        sink_ref: SinkRef,
+        volume: Option<u32>,
+        proplist: Proplist,
+    ) -> Result<Option<u32>> {
+        let reply = self.send_command::<_, PlaySampleReply>(command::PlaySample {
+            sink_ref,
+            volume: volume.unwrap_or(VOLUME_INVALID),
+            sample_name: sample_name.into(),
+            proplist,
+        }).await?;
+
+        Ok(reply.sink_input_index)
+    }
+
+    pub async fn remove_sample(&self, name: impl Into<String>) -> Result<()> {
+        self.send_command::<_, ()>(command::RemoveSample { name: name.into() }).await
+    }
+
+    pub async fn get_sample_info(&self, sample_ref: SampleRef) -> Result<SampleInfo> {
+        self.send_command::<_, SampleInfo>(command::GetSampleInfo { sample_ref }).await
+    }
+
+    pub async fn list_samples(&self) -> Result<Vec<SampleInfo>> {
+        self.send_command::<_, Vec<SampleInfo>>(command::GetSampleInfoList).await
+    }
+
+    /// The server drops the connection on commands it does not know, so check before sending them
+    async fn ensure_protocol_version(&self, since: u32, what: &str) -> Result<()> {
+        let version = self.protocol_version().await;
+        ensure!(version >= since, "{} requires protocol v{}, server speaks v{}", what, since, version);
+
+        Ok(())
+    }
+
+    /// Returns a stream of server events for the facilities selected by `mask`,
+    /// e.g. `SubscriptionMask(SubscriptionMask::SINK | SubscriptionMask::SINK_INPUT)`.
+    ///
+    /// Any number of subscriptions can be active at once, each receives the events matching its own mask.
+    /// A subscription ends when its stream is dropped.
+    pub async fn subscribe(&self, mask: SubscriptionMask) -> Result<impl Stream<Item = SubscriptionEvent>> {
+        let (events_tx, events_rx) = mpsc::unbounded();
+
+        self.inner.lock().await.subscribers.push((mask, events_tx.clone()));
+
+        if let Err(err) = self.update_subscription().await {
+            self.inner.lock().await.subscribers
+                .retain(|(_, subscriber_tx)| !subscriber_tx.same_receiver(&events_tx));
+
+            return Err(err).context("Subscribing failed");
+        }
+
+        Ok(Subscription {
+            events_rx,
+            client: self.clone(),
+        })
+    }
+
+    /// Sends the mask covering all live subscribers to the server, if it changed
+    async fn update_subscription(&self) -> Result<()> {
+        let _subscribe_guard = self.subscribe_lock.lock().await;
+
+        let mask = {
+            let mut inner = self.inner.lock().await;
+            let mask = inner.subscription_mask();
+
+            if mask == inner.server_subscription_mask {
+                return Ok(());
+            }
+
+            mask
+        };
+
+        self.send_command::<_, ()>(command::Subscribe { mask }).await?;
+        self.inner.lock().await.server_subscription_mask = mask;
+
+        Ok(())
+    }
+
+    /// Returns a stream of server-initiated commands that are not covered by `subscribe` or `PlaybackStream::events`,
+    /// e.g. client events, events of streams nobody listens to and commands that failed to parse.
+    ///
+    /// Each call returns a new stream receiving all events from then on.
+    pub async fn events(&self) -> impl Stream<Item = ServerEvent> {
+        let (events_tx, events_rx) = mpsc::unbounded();
+
+        self.inner.lock().await.event_listeners.push(events_tx);
+
+        events_rx
+    }
+
+    pub(crate) async fn playback_stream_events(&self, channel: u32) -> impl Stream<Item = StreamEvent> {
+        let (events_tx, events_rx) = mpsc::unbounded();
+
+        self.inner.lock().await.playback_stream_listeners
+            .entry(channel)
+            .or_default()
+            .push(events_tx);
+
+        events_rx
+    }
+
     pub(crate) async fn send_command<C, R>(&self, command: C) -> Result<R>
     where
         C: Command + tag_struct::Put,
         R: tag_struct::Pop,
     {
         let tag = self.next_tag().await;
-        let mut packet = TagStruct::new();
+        let protocol_version = self.protocol_version().await;
+        let mut packet = TagStruct::with_protocol_version(protocol_version);
         packet.put(CommandHeader {
             command_kind: C::KIND,
             tag,
@@ -90,6 +599,8 @@ impl Client {
 
         eprintln!("Got reply: {:#?}", reply);
 
+        reply.set_protocol_version(protocol_version);
+
         let parsed_reply = reply.pop::<R>()?;
 
         if !reply.is_empty() {
@@ -105,6 +616,21 @@ impl Client {
         name: impl Into<String>,
         sample_spec: SampleSpec,
         channel_map: ChannelMap,
+    ) -> Result<PlaybackStream> {
+        self.create_playback_stream_with_formats(name, sample_spec, channel_map, Vec::new()).await
+    }
+
+    /// Creates a playback stream offering the given formats, in order of preference.
+    ///
+    /// This allows e.g. passing compressed audio through to HDMI sinks.
+    /// The format picked by the server is available via `PlaybackStream::format`.
+    /// Formats require protocol v21, with older servers `sample_spec` is used.
+    pub async fn create_playback_stream_with_formats(
+        &self,
+        name: impl Into<String>,
+        sample_spec: SampleSpec,
+        channel_map: ChannelMap,
+        formats: Vec<FormatInfo>,
     ) -> Result<PlaybackStream> {
         let volume = ChannelVolume {
             volumes: channel_map.positions.iter().map(|_| VOLUME_NORMAL).collect(),
@@ -114,7 +640,7 @@ impl Client {
             name: name.into(),
             sample_spec,
             channel_map,
-            sink_ref: SinkRef::name("@DEFAULT_SINK@"),
+            sink_ref: SinkRef::name(self.default_sink_name()),
             max_length: u32::MAX,
             corked: false,
             t_length: u32::MAX,
@@ -122,17 +648,43 @@ impl Client {
             min_req: u32::MAX,
             sync_id: self.next_sync_id().await,
             volume,
-
+            no_remap: false,
+            no_remix: false,
+            fix_format: false,
+            fix_rate: false,
+            fix_channels: false,
+            no_move: false,
+            variable_rate: false,
+            muted: false,
+            adjust_latency: false,
+            proplist: Proplist::new(),
+            volume_set: false,
+            early_requests: false,
+            muted_set: false,
+            dont_inhibit_auto_suspend: false,
+            fail_on_suspend: false,
+            relative_volume: false,
+            passthrough: false,
+            formats,
         };
 
         let reply = self.send_command::<_, CreatePlaybackStreamReply>(request).await?;
 
         let channel = reply.index;
-        let stream = PlaybackStream::new(self, channel);
+        let stream = PlaybackStream::new(self, channel, reply.format);
 
         Ok(stream)
     }
 
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    fn default_sink_name(&self) -> String {
+        self.config.default_sink.clone()
+            .unwrap_or_else(|| "@DEFAULT_SINK@".into())
+    }
+
     pub(crate) async fn send_frame(&self, frame: Frame) -> Result<()> {
         self.inner.lock().await.send_frame(frame).await
     }
@@ -157,13 +709,84 @@ impl Client {
     }
 }
 
-async fn load_cookie() -> Result<Vec<u8>> {
-    let path = dirs::config_dir()
-        .unwrap_or_default()
-        .join("pulse/cookie");
-    let cookie = fs::read(path).await?;
+/// Adds the application name and details about the current process to `proplist`.
+/// Properties that are already set are left untouched.
+fn client_proplist(application_name: &str, mut proplist: Proplist) -> Proplist {
+    let mut defaults = vec![
+        (keys::APPLICATION_NAME, Some(application_name.to_owned())),
+        (keys::APPLICATION_PROCESS_ID, Some(process::id().to_string())),
+        (keys::APPLICATION_PROCESS_USER, env::var("USER").ok()),
+    ];
 
-    Ok(cookie)
+    let binary = env::current_exe().ok()
+        .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()));
+    defaults.push((keys::APPLICATION_PROCESS_BINARY, binary));
+
+    let host = std::fs::read_to_string("/proc/sys/kernel/hostname").ok()
+        .map(|host| host.trim().to_owned());
+    defaults.push((keys::APPLICATION_PROCESS_HOST, host));
+
+    for (key, value) in defaults {
+        if let Some(value) = value {
+            if !proplist.contains_key(key) {
+                proplist.set_str(key, &value);
+            }
+        }
+    }
+
+    proplist
+}
+
+async fn connect_tcp(host: &str, port: u16, family: AddressFamily) -> Result<TcpStream> {
+    let addresses = net::lookup_host((host, port)).await
+        .context("Failed to resolve host")?
+        .filter(|address| family.matches(address));
+    let mut last_err = None;
+
+    for address in addresses {
+        match TcpStream::connect(address).await {
+            Ok(conn) => {
+                conn.set_nodelay(true)?;
+                return Ok(conn);
+            },
+            Err(err) => last_err = Some(err),
+        }
+    }
+
+    match last_err {
+        Some(err) => Err(err.into()),
+        None => bail!("Host did not resolve to any usable address"),
+    }
+}
+
+/// Stream returned by `Client::subscribe`. Narrows the server's subscription mask when dropped.
+struct Subscription {
+    events_rx: mpsc::UnboundedReceiver<SubscriptionEvent>,
+    client: Client,
+}
+
+impl Stream for Subscription {
+    type Item = SubscriptionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.events_rx).poll_next(cx)
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        // Closing makes `subscription_mask` skip this subscriber right away
+        self.events_rx.close();
+
+        let client = self.client.clone();
+
+        if let Ok(runtime) = runtime::Handle::try_current() {
+            // Failing here is harmless, events for the dropped mask are still filtered out locally
+            runtime.spawn(async move {
+                client.update_subscription().await.ok();
+            });
+        }
+    }
 }
 
 struct InnerClient {
@@ -171,9 +794,32 @@ struct InnerClient {
     next_tag: Tag,
     reply_senders: BTreeMap<Tag, oneshot::Sender<Result<TagStruct>>>,
     sync_id: u32,
+    protocol_version: u32,
+    client_index: Option<u32>,
+    subscribers: Vec<(SubscriptionMask, mpsc::UnboundedSender<SubscriptionEvent>)>,
+    /// Mask last sent to the server
+    server_subscription_mask: SubscriptionMask,
+    event_listeners: Vec<mpsc::UnboundedSender<ServerEvent>>,
+    /// Listeners for events of playback streams, by channel
+    playback_stream_listeners: BTreeMap<u32, Vec<mpsc::UnboundedSender<StreamEvent>>>,
 }
 
 impl InnerClient {
+    fn new(send_frame: SendFrame) -> Self {
+        Self {
+            send_frame,
+            next_tag: 0,
+            reply_senders: BTreeMap::new(),
+            sync_id: 0,
+            protocol_version: PROTOCOL_VERSION,
+            client_index: None,
+            subscribers: Vec::new(),
+            server_subscription_mask: SubscriptionMask(SubscriptionMask::NULL),
+            event_listeners: Vec::new(),
+            playback_stream_listeners: BTreeMap::new(),
+        }
+    }
+
     fn on_frame(&mut self, frame: Result<Frame>) -> Result<()> {
         eprintln!("Entering on_frame");
 
@@ -187,21 +833,46 @@ impl InnerClient {
         let mut packet = TagStruct::parse(&frame.data)?;
         let command_header = packet.pop::<CommandHeader>()?;
 
+        if command_header.command_kind.is_error() {
+            let error_kind = packet.pop::<ErrorKind>()
+                .unwrap_or(ErrorKind::Unknown);
+
+            self.reply(command_header.tag, Err(error_kind.into()))?;
+
+            return Ok(());
+        }
+
         if !command_header.command_kind.is_reply() {
             match command_header.command_kind {
-                CommandKind::Request => {
-                    eprintln!("[TODO] Received REQUEST: {:#?}", packet);
+                CommandKind::SubscribeEvent => {
+                    match packet.pop::<SubscriptionEvent>() {
+                        Ok(event) => self.dispatch_subscription_event(event),
+                        Err(err) => self.dispatch_server_event(ServerEvent::Malformed {
+                            command_kind: CommandKind::SubscribeEvent,
+                            error: format!("{:#}", err),
+                        }),
+                    }
+
                     return Ok(());
                 },
-                _ => {
-                    eprintln!("[TODO] Received unhandled {:?}: {:#?}", command_header.command_kind, packet);
+                command_kind => {
+                    packet.set_protocol_version(self.protocol_version);
+
+                    match command::pop_server_event(command_kind, &mut packet) {
+                        Ok(event) => self.dispatch_server_event(event),
+                        Err(err) => self.dispatch_server_event(ServerEvent::Malformed {
+                            command_kind,
+                            error: format!("{:#}", err),
+                        }),
+                    }
+
                     return Ok(());
-                }
+                },
             }
         }
 
         let tag = command_header.tag;
-        self.reply(tag, packet);
+        self.reply(tag, Ok(packet))?;
 
         Ok(())
     }
@@ -238,17 +909,72 @@ impl InnerClient {
         Ok(reply_rx)
     }
 
-    fn reply(&mut self, tag: Tag, packet: TagStruct) -> Result<()> {
+    fn reply(&mut self, tag: Tag, reply: Result<TagStruct>) -> Result<()> {
         eprintln!("Reply.");
         self.reply_senders
             .remove(&tag)
             .with_context(|| format!("Received reply with unknown tag {}", tag))?
-            .send(Ok(packet))
+            .send(reply)
             .ok();
 
         Ok(())
     }
 
+    /// Forgets subscribers whose stream was dropped and returns the mask covering the rest
+    fn subscription_mask(&mut self) -> SubscriptionMask {
+        self.subscribers.retain(|(_, events_tx)| !events_tx.is_closed());
+
+        let mask = self.subscribers.iter()
+            .fold(SubscriptionMask::NULL, |mask, (subscriber_mask, _)| mask | subscriber_mask.0);
+
+        SubscriptionMask(mask)
+    }
+
+    fn dispatch_subscription_event(&mut self, event: SubscriptionEvent) {
+        self.subscribers.retain(|(mask, events_tx)| {
+            if !mask.matches(event.facility) {
+                return !events_tx.is_closed();
+            }
+
+            events_tx.unbounded_send(event).is_ok()
+        });
+    }
+
+    /// Routes stream events to the listeners of the owning stream, everything else to `Client::events`
+    fn dispatch_server_event(&mut self, event: ServerEvent) {
+        let event = match event {
+            ServerEvent::Stream { direction: StreamDirection::Playback, channel, event } => {
+                match self.dispatch_playback_stream_event(channel, event) {
+                    Some(event) => ServerEvent::Stream { direction: StreamDirection::Playback, channel, event },
+                    None => return,
+                }
+            },
+            event => event,
+        };
+
+        // Events nobody listens for are dropped
+        self.event_listeners.retain(|events_tx| events_tx.unbounded_send(event.clone()).is_ok());
+    }
+
+    /// Returns the event if the stream has no listeners
+    fn dispatch_playback_stream_event(&mut self, channel: u32, event: StreamEvent) -> Option<StreamEvent> {
+        let killed = event == StreamEvent::Killed;
+
+        let listeners = self.playback_stream_listeners.get_mut(&channel)?;
+        listeners.retain(|events_tx| events_tx.unbounded_send(event.clone()).is_ok());
+        let delivered = !listeners.is_empty();
+
+        if killed || !delivered {
+            self.playback_stream_listeners.remove(&channel);
+        }
+
+        if delivered {
+            None
+        } else {
+            Some(event)
+        }
+    }
+
     fn handle_fatal_error(&mut self, err: Error) {
         // TODO: improve error handling
         let reply_senders = mem::replace(&mut self.reply_senders, BTreeMap::new());
@@ -258,3 +984,239 @@ impl InnerClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{future, FutureExt, StreamExt};
+    use command::{Facility, SubscriptionEventType};
+
+    /// `PA_ERR_NOENTITY` as sent on the wire
+    const ERR_NOENTITY: u32 = 5;
+
+    /// Builds a server-initiated command, which carries no meaningful tag
+    fn server_command(command_kind: CommandKind, put: impl FnOnce(&mut TagStruct)) -> Result<Frame> {
+        let mut packet = TagStruct::new();
+        packet.put(CommandHeader {
+            command_kind,
+            tag: Tag::MAX,
+        });
+        put(&mut packet);
+
+        Frame::command(&packet)
+    }
+
+    /// Returns the next item if one is ready
+    fn next_ready<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+        stream.next().now_or_never().flatten()
+    }
+
+    #[test]
+    fn error_reply_is_returned_as_error_kind() {
+        let mut inner = InnerClient::new(Box::new(|_| panic!()));
+        let tag = inner.next_tag();
+        let mut reply_rx = inner.register_reply(tag).unwrap();
+
+        let mut packet = TagStruct::new();
+        packet.put(CommandHeader {
+            command_kind: CommandKind::Error,
+            tag,
+        });
+        packet.put_u32(ERR_NOENTITY);
+
+        inner.on_frame(Frame::command(&packet)).unwrap();
+
+        let err = reply_rx.try_recv().unwrap()
+            .expect("Reply was not sent")
+            .context("Killing sink input failed")
+            .unwrap_err();
+
+        assert_eq!(ErrorKind::from_error(&err), Some(ErrorKind::NoEntity));
+    }
+
+    #[test]
+    fn malformed_server_command_is_delivered_to_events() {
+        let mut inner = InnerClient::new(Box::new(|_| panic!()));
+        let (events_tx, mut events_rx) = mpsc::unbounded();
+        inner.event_listeners.push(events_tx);
+
+        // Missing the `bytes` field
+        let frame = server_command(CommandKind::Request, |packet| packet.put_u32(7));
+        inner.on_frame(frame).unwrap();
+
+        match next_ready(&mut events_rx) {
+            Some(ServerEvent::Malformed { command_kind: CommandKind::Request, .. }) => {},
+            event => panic!("Unexpected event {:?}", event),
+        }
+    }
+
+    /// Creates a client connected to a fake server that acknowledges every command.
+    /// Returns the client together with the subscription masks the server received.
+    async fn client_with_fake_server() -> (Client, mpsc::UnboundedReceiver<u32>) {
+        let client = Client {
+            inner: Arc::new(Mutex::new(InnerClient::new(Box::new(|_| panic!())))),
+            config: Arc::new(ClientConfig::default()),
+            subscribe_lock: Arc::new(Mutex::new(())),
+        };
+        let (frames_tx, mut frames_rx) = mpsc::unbounded::<Frame>();
+        let (masks_tx, masks_rx) = mpsc::unbounded();
+
+        client.inner.lock().await.send_frame = Box::new(move |frame| {
+            frames_tx.unbounded_send(frame).unwrap();
+            future::ready(Ok(())).boxed()
+        });
+
+        let server_client = client.clone();
+        tokio::spawn(async move {
+            while let Some(frame) = frames_rx.next().await {
+                let mut packet = TagStruct::parse(&frame.data).unwrap();
+                let header = packet.pop::<CommandHeader>().unwrap();
+
+                if header.command_kind == CommandKind::Subscribe {
+                    masks_tx.unbounded_send(packet.pop_u32().unwrap()).unwrap();
+                }
+
+                let mut reply = TagStruct::new();
+                reply.put(CommandHeader {
+                    command_kind: CommandKind::Reply,
+                    tag: header.tag,
+                });
+                server_client.on_frame(Frame::command(&reply)).await;
+            }
+        });
+
+        (client, masks_rx)
+    }
+
+    fn subscription_event(facility: Facility) -> Result<Frame> {
+        server_command(CommandKind::SubscribeEvent, |packet| {
+            packet.put_u32(u8::from(facility).into());
+            packet.put_u32(3);
+        })
+    }
+
+    /// Registers a listener for the playback stream on `channel`, like `Client::playback_stream_events`
+    fn listen_to_playback_stream(inner: &mut InnerClient, channel: u32) -> mpsc::UnboundedReceiver<StreamEvent> {
+        let (events_tx, events_rx) = mpsc::unbounded();
+        inner.playback_stream_listeners.entry(channel).or_default().push(events_tx);
+        events_rx
+    }
+
+    fn listen_to_events(inner: &mut InnerClient) -> mpsc::UnboundedReceiver<ServerEvent> {
+        let (events_tx, events_rx) = mpsc::unbounded();
+        inner.event_listeners.push(events_tx);
+        events_rx
+    }
+
+    #[test]
+    fn request_is_delivered_to_playback_stream_listener() {
+        let mut inner = InnerClient::new(Box::new(|_| panic!()));
+        let mut stream_rx = listen_to_playback_stream(&mut inner, 7);
+        let mut events_rx = listen_to_events(&mut inner);
+
+        let frame = server_command(CommandKind::Request, |packet| {
+            packet.put_u32(7);
+            packet.put_u32(1024);
+        });
+        inner.on_frame(frame).unwrap();
+
+        assert_eq!(next_ready(&mut stream_rx), Some(StreamEvent::Request { bytes: 1024 }));
+        assert_eq!(next_ready(&mut events_rx), None);
+    }
+
+    #[test]
+    fn stream_event_falls_back_to_events_without_stream_listeners() {
+        let mut inner = InnerClient::new(Box::new(|_| panic!()));
+        drop(listen_to_playback_stream(&mut inner, 7));
+        let mut events_rx = listen_to_events(&mut inner);
+
+        let frame = server_command(CommandKind::Request, |packet| {
+            packet.put_u32(7);
+            packet.put_u32(1024);
+        });
+        inner.on_frame(frame).unwrap();
+
+        assert_eq!(next_ready(&mut events_rx), Some(ServerEvent::Stream {
+            direction: StreamDirection::Playback,
+            channel: 7,
+            event: StreamEvent::Request { bytes: 1024 },
+        }));
+        assert!(!inner.playback_stream_listeners.contains_key(&7));
+    }
+
+    #[test]
+    fn killed_playback_stream_loses_its_listeners() {
+        let mut inner = InnerClient::new(Box::new(|_| panic!()));
+        let mut stream_rx = listen_to_playback_stream(&mut inner, 7);
+
+        let frame = server_command(CommandKind::PlaybackStreamKilled, |packet| packet.put_u32(7));
+        inner.on_frame(frame).unwrap();
+
+        assert_eq!(next_ready(&mut stream_rx), Some(StreamEvent::Killed));
+        assert!(!inner.playback_stream_listeners.contains_key(&7));
+    }
+
+    #[test]
+    fn record_stream_event_is_delivered_to_events() {
+        let mut inner = InnerClient::new(Box::new(|_| panic!()));
+        let mut stream_rx = listen_to_playback_stream(&mut inner, 7);
+        let mut events_rx = listen_to_events(&mut inner);
+
+        // Record streams share channel numbers with playback streams
+        let frame = server_command(CommandKind::RecordStreamKilled, |packet| packet.put_u32(7));
+        inner.on_frame(frame).unwrap();
+
+        assert_eq!(next_ready(&mut events_rx), Some(ServerEvent::Stream {
+            direction: StreamDirection::Record,
+            channel: 7,
+            event: StreamEvent::Killed,
+        }));
+        assert_eq!(next_ready(&mut stream_rx), None);
+    }
+
+    #[tokio::test]
+    async fn server_mask_is_the_union_of_all_subscriptions() {
+        let (client, mut masks_rx) = client_with_fake_server().await;
+
+        let _sinks = client.subscribe(SubscriptionMask(SubscriptionMask::SINK)).await.unwrap();
+        assert_eq!(masks_rx.next().await, Some(SubscriptionMask::SINK));
+
+        let _sources = client.subscribe(SubscriptionMask(SubscriptionMask::SOURCE)).await.unwrap();
+        assert_eq!(masks_rx.next().await, Some(SubscriptionMask::SINK | SubscriptionMask::SOURCE));
+
+        // Nothing is sent if the mask doesn't change
+        let _more_sinks = client.subscribe(SubscriptionMask(SubscriptionMask::SINK)).await.unwrap();
+        assert_eq!(next_ready(&mut masks_rx), None);
+    }
+
+    #[tokio::test]
+    async fn dropping_a_subscription_narrows_the_server_mask() {
+        let (client, mut masks_rx) = client_with_fake_server().await;
+
+        let sinks = client.subscribe(SubscriptionMask(SubscriptionMask::SINK)).await.unwrap();
+        let _sources = client.subscribe(SubscriptionMask(SubscriptionMask::SOURCE)).await.unwrap();
+        assert_eq!(masks_rx.next().await, Some(SubscriptionMask::SINK));
+        assert_eq!(masks_rx.next().await, Some(SubscriptionMask::SINK | SubscriptionMask::SOURCE));
+
+        drop(sinks);
+
+        assert_eq!(masks_rx.next().await, Some(SubscriptionMask::SOURCE));
+    }
+
+    #[tokio::test]
+    async fn subscription_events_are_delivered_by_facility() {
+        let (client, _masks_rx) = client_with_fake_server().await;
+
+        let mut sinks = Box::pin(client.subscribe(SubscriptionMask(SubscriptionMask::SINK)).await.unwrap());
+        let mut sources = Box::pin(client.subscribe(SubscriptionMask(SubscriptionMask::SOURCE)).await.unwrap());
+
+        client.on_frame(subscription_event(Facility::Sink)).await;
+
+        assert_eq!(next_ready(&mut sinks), Some(SubscriptionEvent {
+            facility: Facility::Sink,
+            event_type: SubscriptionEventType::New,
+            index: 3,
+        }));
+        assert_eq!(next_ready(&mut sources), None);
+    }
+}
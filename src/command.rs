@@ -1,7 +1,31 @@
 use num_enum::{TryFromPrimitive, IntoPrimitive};
 use anyhow::*;
-use crate::{tag_struct, INVALID_INDEX};
+use crate::{tag_struct, INVALID_INDEX, cookie::Cookie, proplist::{self, Proplist}, format::FormatInfo};
 use tag_struct::{TagStruct, ChannelMap, SampleSpec, ChannelVolume};
+use std::time::Duration;
+
+mod sink;
+pub use self::sink::*;
+mod source;
+pub use self::source::*;
+mod sink_input;
+pub use self::sink_input::*;
+mod source_output;
+pub use self::source_output::*;
+mod client;
+pub use self::client::*;
+mod module;
+pub use self::module::*;
+mod card;
+pub use self::card::*;
+mod sample_cache;
+pub use self::sample_cache::*;
+mod volume;
+pub use self::volume::*;
+mod subscribe;
+pub use self::subscribe::*;
+mod event;
+pub use self::event::*;
 
 pub trait Command {
     const KIND: CommandKind;
@@ -33,7 +57,7 @@ impl tag_struct::Put for CommandHeader {
 
 pub struct Auth {
     pub protocol_version: u32,
-    pub cookie: Vec<u8>,
+    pub cookie: Cookie,
 }
 
 impl Command for Auth {
@@ -43,61 +67,119 @@ impl Command for Auth {
 impl tag_struct::Put for Auth {
     fn put(self, tag_struct: &mut TagStruct) {
         tag_struct.put_u32(self.protocol_version);
-        tag_struct.put_arbitrary(self.cookie);
+        tag_struct.put_arbitrary(self.cookie.into_bytes());
     }
 }
 
-pub struct PlaySample {
-    pub sink_ref: SinkRef,
-    pub volume: u32,
-    pub sample_name: String,
+pub struct SetClientName {
+    /// Must contain `application.name`
+    pub proplist: Proplist,
 }
 
-impl Command for PlaySample {
-    const KIND: CommandKind = CommandKind::PlaySample;
+impl Command for SetClientName {
+    const KIND: CommandKind = CommandKind::SetClientName;
 }
 
-impl tag_struct::Put for PlaySample {
+impl tag_struct::Put for SetClientName {
     fn put(self, tag_struct: &mut TagStruct) {
-        let (sink_index, sink_name) = match self.sink_ref {
-            SinkRef::Index(index) => (index, None),
-            SinkRef::Name(name) => (INVALID_INDEX, Some(name)),
-        };
-
-        tag_struct.put_u32(sink_index);
-        tag_struct.put_string(sink_name);
-        tag_struct.put_u32(self.volume);
-        tag_struct.put_string(self.sample_name);
+        if tag_struct.protocol_version() >= 13 {
+            tag_struct.put_proplist(self.proplist);
+        } else {
+            let name = self.proplist.get_str(proplist::keys::APPLICATION_NAME)
+                .map(String::from);
+
+            tag_struct.put_string(name);
+        }
     }
 }
 
 #[derive(Debug)]
-pub enum SinkRef {
-    Index(u32),
-    Name(String),
+pub struct SetClientNameReply {
+    /// Index of this client on the server. Since v13.
+    pub client_index: Option<u32>,
 }
 
-impl SinkRef {
-    pub fn index(index: u32) -> Self {
-        Self::Index(index)
+impl tag_struct::Pop for SetClientNameReply {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        Ok(Self {
+            client_index: if tag_struct.protocol_version() >= 13 {
+                Some(tag_struct.pop_u32().context("Missing client index field")?)
+            } else {
+                None
+            },
+        })
     }
+}
 
-    pub fn name(name: impl Into<String>) -> Self {
-        Self::Name(name.into())
-    }
+/// Defines a reference to a server object by either its index or its name
+macro_rules! object_ref {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone)]
+        pub enum $name {
+            Index(u32),
+            Name(String),
+        }
+
+        impl $name {
+            pub fn index(index: u32) -> Self {
+                Self::Index(index)
+            }
+
+            pub fn name(name: impl Into<String>) -> Self {
+                Self::Name(name.into())
+            }
+        }
+
+        /// Encoded as index and name, one of which is invalid
+        impl tag_struct::Put for $name {
+            fn put(self, tag_struct: &mut TagStruct) {
+                let (index, name) = match self {
+                    Self::Index(index) => (index, None),
+                    Self::Name(name) => (INVALID_INDEX, Some(name)),
+                };
+
+                tag_struct.put_u32(index);
+                tag_struct.put_string(name);
+            }
+        }
+    };
 }
 
+object_ref!(
+    /// A sink, by index or name
+    SinkRef
+);
+object_ref!(
+    /// A source, by index or name
+    SourceRef
+);
+object_ref!(
+    /// A card, by index or name
+    CardRef
+);
+object_ref!(
+    /// A sample in the sample cache, by index or name
+    SampleRef
+);
+
+/// The upper bits of the version carry the SHM/memfd flags
+const PROTOCOL_VERSION_MASK: u32 = 0x0000_FFFF;
+
 #[derive(Debug)]
 pub struct AuthReply {
+    /// Highest protocol version supported by the server
     pub protocol_version: u32,
 }
 
 impl tag_struct::Pop for AuthReply {
     fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.pop_u32().context("Missing version field")?;
+
         Ok(Self {
-            protocol_version: tag_struct.pop_u32().context("Missing version field")?,
+            protocol_version: version & PROTOCOL_VERSION_MASK,
         })
-    }   
+    }
 }
 
 #[derive(Debug)]
@@ -115,6 +197,32 @@ pub struct CreatePlaybackStream {
     pub min_req: u32, //PA_TAG_U32, &attr.minreq,
     pub sync_id: u32, //PA_TAG_U32, &syncid,
     pub volume: ChannelVolume, //PA_TAG_CVOLUME, &volume,
+    // Since v12
+    pub no_remap: bool,
+    pub no_remix: bool,
+    pub fix_format: bool,
+    pub fix_rate: bool,
+    pub fix_channels: bool,
+    pub no_move: bool,
+    pub variable_rate: bool,
+    // Since v13
+    pub muted: bool,
+    pub adjust_latency: bool,
+    /// Sent instead of `name` since v13, which is stored as `media.name` unless already set
+    pub proplist: Proplist,
+    // Since v14
+    pub volume_set: bool,
+    pub early_requests: bool,
+    // Since v15
+    pub muted_set: bool,
+    pub dont_inhibit_auto_suspend: bool,
+    pub fail_on_suspend: bool,
+    // Since v17
+    pub relative_volume: bool,
+    // Since v18
+    pub passthrough: bool,
+    /// Formats the client can provide, in order of preference. Since v21.
+    pub formats: Vec<FormatInfo>,
 }
 
 impl Command for CreatePlaybackStream {
@@ -122,18 +230,18 @@ impl Command for CreatePlaybackStream {
 }
 
 impl tag_struct::Put for CreatePlaybackStream {
-    fn put(self, tag_struct: &mut TagStruct) {
-        tag_struct.put_string(self.name);
+    fn put(mut self, tag_struct: &mut TagStruct) {
+        let version = tag_struct.protocol_version();
+
+        if version < 13 {
+            tag_struct.put_string(self.name);
+        } else if !self.proplist.contains_key(proplist::keys::MEDIA_NAME) {
+            self.proplist.set_str(proplist::keys::MEDIA_NAME, &self.name);
+        }
+
         tag_struct.put_sample_spec(self.sample_spec);
         tag_struct.put_channel_map(self.channel_map);
-        
-        let (sink_index, sink_name) = match self.sink_ref {
-            SinkRef::Index(index) => (index, None),
-            SinkRef::Name(name) => (INVALID_INDEX, Some(name)),
-        };
-
-        tag_struct.put_u32(sink_index);
-        tag_struct.put_string(sink_name);
+        tag_struct.put(self.sink_ref);
 
         tag_struct.put_u32(self.max_length);
         tag_struct.put_bool(self.corked);
@@ -142,6 +250,50 @@ impl tag_struct::Put for CreatePlaybackStream {
         tag_struct.put_u32(self.min_req);
         tag_struct.put_u32(self.sync_id);
         tag_struct.put_channel_volume(self.volume);
+
+        if version >= 12 {
+            tag_struct.put_bool(self.no_remap);
+            tag_struct.put_bool(self.no_remix);
+            tag_struct.put_bool(self.fix_format);
+            tag_struct.put_bool(self.fix_rate);
+            tag_struct.put_bool(self.fix_channels);
+            tag_struct.put_bool(self.no_move);
+            tag_struct.put_bool(self.variable_rate);
+        }
+
+        if version >= 13 {
+            tag_struct.put_bool(self.muted);
+            tag_struct.put_bool(self.adjust_latency);
+            tag_struct.put_proplist(self.proplist);
+        }
+
+        if version >= 14 {
+            tag_struct.put_bool(self.volume_set);
+            tag_struct.put_bool(self.early_requests);
+        }
+
+        if version >= 15 {
+            tag_struct.put_bool(self.muted_set);
+            tag_struct.put_bool(self.dont_inhibit_auto_suspend);
+            tag_struct.put_bool(self.fail_on_suspend);
+        }
+
+        if version >= 17 {
+            tag_struct.put_bool(self.relative_volume);
+        }
+
+        if version >= 18 {
+            tag_struct.put_bool(self.passthrough);
+        }
+
+        if version >= 21 {
+            let num_formats = self.formats.len().min(u8::MAX as usize);
+            tag_struct.put_u8(num_formats as u8);
+
+            for format in self.formats.into_iter().take(num_formats) {
+                tag_struct.put_format_info(format);
+            }
+        }
     }
 }
 
@@ -150,18 +302,79 @@ pub struct CreatePlaybackStreamReply {
     pub index: u32,
     pub sink_input: u32,
     pub missing: u32,
+    /// Buffer attributes chosen by the server. Since v9.
+    pub buffer_attr: Option<BufferAttr>,
+    /// Since v12
+    pub sample_spec: Option<SampleSpec>,
+    /// Since v12
+    pub channel_map: Option<ChannelMap>,
+    /// Index of the sink the stream was connected to. Since v12.
+    pub sink_index: Option<u32>,
+    /// Since v12
+    pub sink_name: Option<String>,
+    /// Since v12
+    pub suspended: Option<bool>,
+    /// Configured latency of the sink. Since v13.
+    pub sink_latency: Option<Duration>,
+    /// Format chosen by the server. Since v21.
+    pub format: Option<FormatInfo>,
 }
 
 impl tag_struct::Pop for CreatePlaybackStreamReply {
     fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
         Ok(Self {
             index: tag_struct.pop_u32().context("Missing index field")?,
             sink_input: tag_struct.pop_u32().context("Missing sink_input field")?,
             missing: tag_struct.pop_u32().context("Missing missing field")?,
+            buffer_attr: if version >= 9 {
+                Some(BufferAttr {
+                    max_length: tag_struct.pop_u32().context("Missing max_length field")?,
+                    t_length: tag_struct.pop_u32().context("Missing t_length field")?,
+                    prebuf: tag_struct.pop_u32().context("Missing prebuf field")?,
+                    min_req: tag_struct.pop_u32().context("Missing min_req field")?,
+                })
+            } else {
+                None
+            },
+            sample_spec: since(version, 12, || tag_struct.pop_sample_spec())?,
+            channel_map: since(version, 12, || tag_struct.pop_channel_map())?,
+            sink_index: since(version, 12, || tag_struct.pop_u32())?,
+            sink_name: since(version, 12, || tag_struct.pop_string())?.flatten(),
+            suspended: since(version, 12, || tag_struct.pop_bool())?,
+            sink_latency: since(version, 13, || tag_struct.pop_usec())?,
+            format: since(version, 21, || tag_struct.pop_format_info())?,
         })
     }
 }
 
+/// Pops a field that only exists since protocol version `since`
+pub(crate) fn since<T>(version: u32, since: u32, pop: impl FnOnce() -> Result<T>) -> Result<Option<T>> {
+    if version >= since {
+        pop().map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Maps `INVALID_INDEX` to `None`
+pub(crate) fn optional_index(index: u32) -> Option<u32> {
+    if index == INVALID_INDEX {
+        None
+    } else {
+        Some(index)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BufferAttr {
+    pub max_length: u32,
+    pub t_length: u32,
+    pub prebuf: u32,
+    pub min_req: u32,
+}
+
 pub struct GetServerInfo;
 
 impl Command for GetServerInfo {
@@ -183,6 +396,8 @@ pub struct ServerInfo {
     pub default_sink_name: Option<String>,
     pub default_source_name: Option<String>,
     pub instance_cookie: u32,
+    /// Default channel map. Since v15.
+    pub channel_map: Option<ChannelMap>,
 }
 
 impl tag_struct::Pop for ServerInfo {
@@ -196,6 +411,7 @@ impl tag_struct::Pop for ServerInfo {
             default_sink_name: tag_struct.pop_string()?,
             default_source_name: tag_struct.pop_string()?,
             instance_cookie: tag_struct.pop_u32()?,
+            channel_map: since(tag_struct.protocol_version(), 15, || tag_struct.pop_channel_map())?,
         })
     }
 }
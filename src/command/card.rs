@@ -0,0 +1,210 @@
+use anyhow::*;
+use num_enum::{TryFromPrimitive, IntoPrimitive};
+use crate::{tag_struct::{self, TagStruct}, proplist::Proplist};
+use super::{Command, CommandKind, CardRef, PortAvailable, since, optional_index};
+
+pub struct GetCardInfo {
+    pub card_ref: CardRef,
+}
+
+impl Command for GetCardInfo {
+    const KIND: CommandKind = CommandKind::GetCardInfo;
+}
+
+impl tag_struct::Put for GetCardInfo {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.card_ref);
+    }
+}
+
+pub struct GetCardInfoList;
+
+impl Command for GetCardInfoList {
+    const KIND: CommandKind = CommandKind::GetCardInfoList;
+}
+
+impl tag_struct::Put for GetCardInfoList {
+    fn put(self, _tag_struct: &mut TagStruct) {
+    }
+}
+
+pub struct SetCardProfile {
+    pub card_ref: CardRef,
+    pub profile: String,
+}
+
+impl Command for SetCardProfile {
+    const KIND: CommandKind = CommandKind::SetCardProfile;
+}
+
+impl tag_struct::Put for SetCardProfile {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.card_ref);
+        tag_struct.put_string(Some(self.profile));
+    }
+}
+
+/// Since v27
+pub struct SetPortLatencyOffset {
+    pub card_ref: CardRef,
+    pub port: String,
+    /// In microseconds, may be negative
+    pub offset: i64,
+}
+
+impl Command for SetPortLatencyOffset {
+    const KIND: CommandKind = CommandKind::SetPortLatencyOffset;
+}
+
+impl tag_struct::Put for SetPortLatencyOffset {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.card_ref);
+        tag_struct.put_string(self.port);
+        tag_struct.put_s64(self.offset);
+    }
+}
+
+/// A sound card, grouping sinks and sources that are switched between profiles together.
+/// Only available since v15.
+#[derive(Debug)]
+pub struct CardInfo {
+    pub index: u32,
+    pub name: String,
+    /// Index of the module that owns this card
+    pub owner_module: Option<u32>,
+    pub driver: Option<String>,
+    pub profiles: Vec<CardProfileInfo>,
+    /// Name of the active profile
+    pub active_profile: Option<String>,
+    pub proplist: Proplist,
+    /// Empty before v26
+    pub ports: Vec<CardPortInfo>,
+}
+
+impl CardInfo {
+    pub fn profile(&self, name: &str) -> Option<&CardProfileInfo> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+}
+
+impl tag_struct::Pop for CardInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+            name: tag_struct.pop_string()?.context("Missing name field")?,
+            owner_module: optional_index(tag_struct.pop_u32()?),
+            driver: tag_struct.pop_string()?,
+            profiles: {
+                let num_profiles = tag_struct.pop_u32()?;
+
+                (0..num_profiles)
+                    .map(|_| tag_struct.pop::<CardProfileInfo>())
+                    .collect::<Result<_>>()?
+            },
+            active_profile: tag_struct.pop_string()?,
+            proplist: tag_struct.pop_proplist()?,
+            ports: since(version, 26, || {
+                let num_ports = tag_struct.pop_u32()?;
+
+                (0..num_ports)
+                    .map(|_| tag_struct.pop::<CardPortInfo>())
+                    .collect::<Result<_>>()
+            })?.unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CardProfileInfo {
+    pub name: String,
+    pub description: Option<String>,
+    /// Number of sinks this profile would create
+    pub n_sinks: u32,
+    /// Number of sources this profile would create
+    pub n_sources: u32,
+    /// Higher is better
+    pub priority: u32,
+    /// Whether the profile can currently be activated. Since v29.
+    pub available: Option<bool>,
+}
+
+impl tag_struct::Pop for CardProfileInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            name: tag_struct.pop_string()?.context("Missing profile name field")?,
+            description: tag_struct.pop_string()?,
+            n_sinks: tag_struct.pop_u32()?,
+            n_sources: tag_struct.pop_u32()?,
+            priority: tag_struct.pop_u32()?,
+            // Sent as a flag, not as `PortAvailable`
+            available: since(version, 29, || Ok(tag_struct.pop_u32()? != 0))?,
+        })
+    }
+}
+
+/// A port of a card, listing the profiles it is part of
+#[derive(Debug)]
+pub struct CardPortInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub priority: u32,
+    pub available: PortAvailable,
+    pub direction: Direction,
+    pub proplist: Proplist,
+    /// Names of the profiles this port belongs to
+    pub profiles: Vec<String>,
+    /// Added to the latency reported for this port, in microseconds. Since v27.
+    pub latency_offset: Option<i64>,
+    /// Ports with the same group share their availability. Since v34.
+    pub availability_group: Option<String>,
+    /// Kind of port, e.g. speaker or headphones. Since v34.
+    pub port_type: Option<u32>,
+}
+
+impl tag_struct::Pop for CardPortInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            name: tag_struct.pop_string()?.context("Missing port name field")?,
+            description: tag_struct.pop_string()?,
+            priority: tag_struct.pop_u32()?,
+            available: tag_struct.pop::<PortAvailable>()?,
+            direction: tag_struct.pop::<Direction>()?,
+            proplist: tag_struct.pop_proplist()?,
+            profiles: {
+                let num_profiles = tag_struct.pop_u32()?;
+
+                (0..num_profiles)
+                    .map(|_| tag_struct.pop_string()?.context("Missing port profile name"))
+                    .collect::<Result<_>>()?
+            },
+            latency_offset: since(version, 27, || tag_struct.pop_s64())?,
+            availability_group: since(version, 34, || tag_struct.pop_string())?.flatten(),
+            port_type: since(version, 34, || tag_struct.pop_u32())?,
+        })
+    }
+}
+
+/// Whether a port is used for playback, recording or both
+#[derive(Debug, TryFromPrimitive, IntoPrimitive, Copy, Clone, PartialEq)]
+#[repr(u8)]
+pub enum Direction {
+    Output = 1,
+    Input = 2,
+    Both = 3,
+}
+
+impl tag_struct::Pop for Direction {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let direction = tag_struct.pop_u8()?;
+        let direction = Self::try_from_primitive(direction)
+            .map_err(|_| anyhow!("Unknown port direction {}", direction))?;
+
+        Ok(direction)
+    }
+}
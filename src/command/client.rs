@@ -0,0 +1,68 @@
+use anyhow::*;
+use crate::{tag_struct::{self, TagStruct}, proplist::Proplist};
+use super::{Command, CommandKind, since, optional_index};
+
+pub struct GetClientInfo {
+    pub index: u32,
+}
+
+impl Command for GetClientInfo {
+    const KIND: CommandKind = CommandKind::GetClientInfo;
+}
+
+impl tag_struct::Put for GetClientInfo {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+    }
+}
+
+pub struct GetClientInfoList;
+
+impl Command for GetClientInfoList {
+    const KIND: CommandKind = CommandKind::GetClientInfoList;
+}
+
+impl tag_struct::Put for GetClientInfoList {
+    fn put(self, _tag_struct: &mut TagStruct) {
+    }
+}
+
+pub struct KillClient {
+    pub index: u32,
+}
+
+impl Command for KillClient {
+    const KIND: CommandKind = CommandKind::KillClient;
+}
+
+impl tag_struct::Put for KillClient {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+    }
+}
+
+/// A client connected to the server
+#[derive(Debug)]
+pub struct ClientInfo {
+    pub index: u32,
+    pub name: Option<String>,
+    /// Index of the module that owns this client
+    pub owner_module: Option<u32>,
+    pub driver: Option<String>,
+    /// Since v13
+    pub proplist: Option<Proplist>,
+}
+
+impl tag_struct::Pop for ClientInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+            name: tag_struct.pop_string()?,
+            owner_module: optional_index(tag_struct.pop_u32()?),
+            driver: tag_struct.pop_string()?,
+            proplist: since(version, 13, || tag_struct.pop_proplist())?,
+        })
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::*;
+use crate::{tag_struct::TagStruct, proplist::Proplist};
+use super::{CommandKind, BufferAttr, since};
+use std::time::Duration;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StreamDirection {
+    Playback,
+    Record,
+}
+
+/// Buffer metrics of a record stream
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RecordBufferAttr {
+    pub max_length: u32,
+    pub frag_size: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StreamBufferAttr {
+    Playback(BufferAttr),
+    Record(RecordBufferAttr),
+}
+
+/// A server-initiated command concerning a single stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    /// The server wants `bytes` more bytes of audio data. Playback only.
+    Request {
+        bytes: u32,
+    },
+    /// The server received more data than fits into the buffer. Playback only.
+    Overflow,
+    /// The server ran out of data. Playback only.
+    Underflow {
+        /// Write index at which the underflow happened. Since v23.
+        offset: Option<i64>,
+    },
+    /// Playback started after prebuffering. Since v13.
+    Started,
+    /// The stream was removed by the server, e.g. because its device went away
+    Killed,
+    /// The device of the stream was suspended or resumed. Since v12.
+    Suspended {
+        suspended: bool,
+    },
+    /// The stream was moved to another sink or source. Since v12.
+    Moved {
+        device_index: u32,
+        device_name: Option<String>,
+        suspended: bool,
+        /// Since v13
+        buffer_attr: Option<StreamBufferAttr>,
+        /// Configured latency of the new device. Since v13.
+        configured_latency: Option<Duration>,
+    },
+    /// The server changed the buffer metrics. Since v15.
+    BufferAttrChanged {
+        buffer_attr: StreamBufferAttr,
+        configured_latency: Duration,
+    },
+    /// Named event sent to the stream, e.g. `request-cork`. Since v15.
+    Event {
+        name: String,
+        proplist: Proplist,
+    },
+}
+
+/// A server-initiated command that is not a reply, see `Client::events`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerEvent {
+    /// An event for a stream that nobody listens to via `PlaybackStream::events`,
+    /// or for a record stream
+    Stream {
+        direction: StreamDirection,
+        channel: u32,
+        event: StreamEvent,
+    },
+    /// Named event sent to this client. Since v15.
+    Client {
+        name: String,
+        proplist: Proplist,
+    },
+    /// Message from a server extension, e.g. `module-stream-restore`. The payload is extension specific and not parsed.
+    /// Since v14.
+    Extension {
+        index: u32,
+        name: Option<String>,
+    },
+    /// A server-initiated command this crate does not handle
+    Unhandled {
+        command_kind: CommandKind,
+    },
+    /// A server-initiated command that could not be parsed
+    Malformed {
+        command_kind: CommandKind,
+        /// Description of what failed to parse
+        error: String,
+    },
+}
+
+/// Parses a server-initiated command. Subscription events are handled separately.
+pub(crate) fn pop_server_event(command_kind: CommandKind, tag_struct: &mut TagStruct) -> Result<ServerEvent> {
+    let version = tag_struct.protocol_version();
+
+    let (direction, event) = match command_kind {
+        CommandKind::Request => (StreamDirection::Playback, StreamEventKind::Request),
+        CommandKind::Overflow => (StreamDirection::Playback, StreamEventKind::Overflow),
+        CommandKind::Underflow => (StreamDirection::Playback, StreamEventKind::Underflow),
+        CommandKind::Started => (StreamDirection::Playback, StreamEventKind::Started),
+        CommandKind::PlaybackStreamKilled => (StreamDirection::Playback, StreamEventKind::Killed),
+        CommandKind::RecordStreamKilled => (StreamDirection::Record, StreamEventKind::Killed),
+        CommandKind::PlaybackStreamSuspended => (StreamDirection::Playback, StreamEventKind::Suspended),
+        CommandKind::RecordStreamSuspended => (StreamDirection::Record, StreamEventKind::Suspended),
+        CommandKind::PlaybackStreamMoved => (StreamDirection::Playback, StreamEventKind::Moved),
+        CommandKind::RecordStreamMoved => (StreamDirection::Record, StreamEventKind::Moved),
+        CommandKind::PlaybackBufferAttrChanged => (StreamDirection::Playback, StreamEventKind::BufferAttrChanged),
+        CommandKind::RecordBufferAttrChanged => (StreamDirection::Record, StreamEventKind::BufferAttrChanged),
+        CommandKind::PlaybackStreamEvent => (StreamDirection::Playback, StreamEventKind::Event),
+        CommandKind::RecordStreamEvent => (StreamDirection::Record, StreamEventKind::Event),
+        CommandKind::ClientEvent => {
+            return Ok(ServerEvent::Client {
+                name: tag_struct.pop_string()?.context("Missing event name field")?,
+                proplist: tag_struct.pop_proplist()?,
+            });
+        },
+        CommandKind::Extension => {
+            return Ok(ServerEvent::Extension {
+                index: tag_struct.pop_u32().context("Missing index field")?,
+                name: tag_struct.pop_string()?,
+            });
+        },
+        command_kind => return Ok(ServerEvent::Unhandled { command_kind }),
+    };
+
+    let channel = tag_struct.pop_u32().context("Missing channel field")?;
+
+    let event = match event {
+        StreamEventKind::Request => StreamEvent::Request {
+            bytes: tag_struct.pop_u32().context("Missing bytes field")?,
+        },
+        StreamEventKind::Overflow => StreamEvent::Overflow,
+        StreamEventKind::Underflow => StreamEvent::Underflow {
+            offset: since(version, 23, || tag_struct.pop_s64())?,
+        },
+        StreamEventKind::Started => StreamEvent::Started,
+        StreamEventKind::Killed => StreamEvent::Killed,
+        StreamEventKind::Suspended => StreamEvent::Suspended {
+            suspended: tag_struct.pop_bool()?,
+        },
+        StreamEventKind::Moved => StreamEvent::Moved {
+            device_index: tag_struct.pop_u32().context("Missing device index field")?,
+            device_name: tag_struct.pop_string()?,
+            suspended: tag_struct.pop_bool()?,
+            buffer_attr: since(version, 13, || pop_buffer_attr(direction, tag_struct))?,
+            configured_latency: since(version, 13, || tag_struct.pop_usec())?,
+        },
+        StreamEventKind::BufferAttrChanged => StreamEvent::BufferAttrChanged {
+            buffer_attr: pop_buffer_attr(direction, tag_struct)?,
+            configured_latency: tag_struct.pop_usec()?,
+        },
+        StreamEventKind::Event => StreamEvent::Event {
+            name: tag_struct.pop_string()?.context("Missing event name field")?,
+            proplist: tag_struct.pop_proplist()?,
+        },
+    };
+
+    Ok(ServerEvent::Stream { direction, channel, event })
+}
+
+enum StreamEventKind {
+    Request,
+    Overflow,
+    Underflow,
+    Started,
+    Killed,
+    Suspended,
+    Moved,
+    BufferAttrChanged,
+    Event,
+}
+
+fn pop_buffer_attr(direction: StreamDirection, tag_struct: &mut TagStruct) -> Result<StreamBufferAttr> {
+    let buffer_attr = match direction {
+        StreamDirection::Playback => StreamBufferAttr::Playback(BufferAttr {
+            max_length: tag_struct.pop_u32()?,
+            t_length: tag_struct.pop_u32()?,
+            prebuf: tag_struct.pop_u32()?,
+            min_req: tag_struct.pop_u32()?,
+        }),
+        StreamDirection::Record => StreamBufferAttr::Record(RecordBufferAttr {
+            max_length: tag_struct.pop_u32()?,
+            frag_size: tag_struct.pop_u32()?,
+        }),
+    };
+
+    Ok(buffer_attr)
+}
@@ -0,0 +1,106 @@
+use anyhow::*;
+use crate::{tag_struct::{self, TagStruct}, proplist::Proplist};
+use super::{Command, CommandKind, since, optional_index};
+
+pub struct GetModuleInfo {
+    pub index: u32,
+}
+
+impl Command for GetModuleInfo {
+    const KIND: CommandKind = CommandKind::GetModuleInfo;
+}
+
+impl tag_struct::Put for GetModuleInfo {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+    }
+}
+
+pub struct GetModuleInfoList;
+
+impl Command for GetModuleInfoList {
+    const KIND: CommandKind = CommandKind::GetModuleInfoList;
+}
+
+impl tag_struct::Put for GetModuleInfoList {
+    fn put(self, _tag_struct: &mut TagStruct) {
+    }
+}
+
+pub struct LoadModule {
+    pub name: String,
+    pub argument: String,
+}
+
+impl Command for LoadModule {
+    const KIND: CommandKind = CommandKind::LoadModule;
+}
+
+impl tag_struct::Put for LoadModule {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_string(self.name);
+        tag_struct.put_string(self.argument);
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadModuleReply {
+    /// Index of the loaded module
+    pub index: u32,
+}
+
+impl tag_struct::Pop for LoadModuleReply {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+        })
+    }
+}
+
+pub struct UnloadModule {
+    pub index: u32,
+}
+
+impl Command for UnloadModule {
+    const KIND: CommandKind = CommandKind::UnloadModule;
+}
+
+impl tag_struct::Put for UnloadModule {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+    }
+}
+
+/// A module loaded into the server
+#[derive(Debug)]
+pub struct ModuleInfo {
+    pub index: u32,
+    pub name: String,
+    /// Arguments the module was loaded with, e.g. `sink_name=foo rate=48000`
+    pub argument: Option<String>,
+    /// Usage counter, if known
+    pub n_used: Option<u32>,
+    /// Always false, autoloading was removed from the server. Only sent before v15.
+    pub auto_unload: Option<bool>,
+    /// Since v15
+    pub proplist: Option<Proplist>,
+}
+
+impl tag_struct::Pop for ModuleInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+            name: tag_struct.pop_string()?.context("Missing name field")?,
+            argument: tag_struct.pop_string()?,
+            n_used: optional_index(tag_struct.pop_u32()?),
+            auto_unload: if version < 15 {
+                Some(tag_struct.pop_bool()?)
+            } else {
+                None
+            },
+            proplist: since(version, 15, || tag_struct.pop_proplist())?,
+        })
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::*;
+use crate::{tag_struct::{self, TagStruct, SampleSpec, ChannelMap, ChannelVolume}, proplist::Proplist};
+use super::{Command, CommandKind, SinkRef, SampleRef, since};
+use std::time::Duration;
+
+pub struct CreateUploadStream {
+    pub name: String,
+    pub sample_spec: SampleSpec,
+    pub channel_map: ChannelMap,
+    /// Total length of the sample in bytes
+    pub length: u32,
+    /// Since v13
+    pub proplist: Proplist,
+}
+
+impl Command for CreateUploadStream {
+    const KIND: CommandKind = CommandKind::CreateUploadStream;
+}
+
+impl tag_struct::Put for CreateUploadStream {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_string(self.name);
+        tag_struct.put_sample_spec(self.sample_spec);
+        tag_struct.put_channel_map(self.channel_map);
+        tag_struct.put_u32(self.length);
+
+        if tag_struct.protocol_version() >= 13 {
+            tag_struct.put_proplist(self.proplist);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateUploadStreamReply {
+    /// Channel the sample data has to be sent on
+    pub channel: u32,
+    /// Number of bytes the server expects
+    pub length: u32,
+}
+
+impl tag_struct::Pop for CreateUploadStreamReply {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        Ok(Self {
+            channel: tag_struct.pop_u32().context("Missing channel field")?,
+            length: tag_struct.pop_u32().context("Missing length field")?,
+        })
+    }
+}
+
+/// Stores the uploaded data in the sample cache
+pub struct FinishUploadStream {
+    pub channel: u32,
+}
+
+impl Command for FinishUploadStream {
+    const KIND: CommandKind = CommandKind::FinishUploadStream;
+}
+
+impl tag_struct::Put for FinishUploadStream {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.channel);
+    }
+}
+
+/// Discards the upload without storing anything
+pub struct DeleteUploadStream {
+    pub channel: u32,
+}
+
+impl Command for DeleteUploadStream {
+    const KIND: CommandKind = CommandKind::DeleteUploadStream;
+}
+
+impl tag_struct::Put for DeleteUploadStream {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.channel);
+    }
+}
+
+pub struct PlaySample {
+    pub sink_ref: SinkRef,
+    pub volume: u32,
+    pub sample_name: String,
+    /// Properties of the created sink input. Since v13.
+    pub proplist: Proplist,
+}
+
+impl Command for PlaySample {
+    const KIND: CommandKind = CommandKind::PlaySample;
+}
+
+impl tag_struct::Put for PlaySample {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.sink_ref);
+        tag_struct.put_u32(self.volume);
+        tag_struct.put_string(self.sample_name);
+
+        if tag_struct.protocol_version() >= 13 {
+            tag_struct.put_proplist(self.proplist);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PlaySampleReply {
+    /// Index of the sink input playing the sample. Since v13.
+    pub sink_input_index: Option<u32>,
+}
+
+impl tag_struct::Pop for PlaySampleReply {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            sink_input_index: since(version, 13, || tag_struct.pop_u32())?,
+        })
+    }
+}
+
+pub struct RemoveSample {
+    pub name: String,
+}
+
+impl Command for RemoveSample {
+    const KIND: CommandKind = CommandKind::RemoveSample;
+}
+
+impl tag_struct::Put for RemoveSample {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_string(self.name);
+    }
+}
+
+pub struct GetSampleInfo {
+    pub sample_ref: SampleRef,
+}
+
+impl Command for GetSampleInfo {
+    const KIND: CommandKind = CommandKind::GetSampleInfo;
+}
+
+impl tag_struct::Put for GetSampleInfo {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.sample_ref);
+    }
+}
+
+pub struct GetSampleInfoList;
+
+impl Command for GetSampleInfoList {
+    const KIND: CommandKind = CommandKind::GetSampleInfoList;
+}
+
+impl tag_struct::Put for GetSampleInfoList {
+    fn put(self, _tag_struct: &mut TagStruct) {
+    }
+}
+
+/// A sample stored in the server's sample cache
+#[derive(Debug)]
+pub struct SampleInfo {
+    pub index: u32,
+    pub name: String,
+    /// Default volume used when playing the sample
+    pub volume: ChannelVolume,
+    pub duration: Duration,
+    pub sample_spec: SampleSpec,
+    pub channel_map: ChannelMap,
+    /// Length in bytes
+    pub length: u32,
+    /// Whether the sample is only loaded from `filename` when first played
+    pub lazy: bool,
+    pub filename: Option<String>,
+    /// Since v13
+    pub proplist: Option<Proplist>,
+}
+
+impl tag_struct::Pop for SampleInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+            name: tag_struct.pop_string()?.context("Missing name field")?,
+            volume: tag_struct.pop_channel_volume()?,
+            duration: tag_struct.pop_usec()?,
+            sample_spec: tag_struct.pop_sample_spec()?,
+            channel_map: tag_struct.pop_channel_map()?,
+            length: tag_struct.pop_u32()?,
+            lazy: tag_struct.pop_bool()?,
+            filename: tag_struct.pop_string()?,
+            proplist: since(version, 13, || tag_struct.pop_proplist())?,
+        })
+    }
+}
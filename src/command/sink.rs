@@ -0,0 +1,301 @@
+use anyhow::*;
+use num_enum::{TryFromPrimitive, IntoPrimitive};
+use crate::{INVALID_INDEX, tag_struct::{self, TagStruct, SampleSpec, ChannelMap, ChannelVolume}, proplist::Proplist, format::FormatInfo};
+use super::{Command, CommandKind, SinkRef, since, optional_index};
+use std::time::Duration;
+
+pub struct GetSinkInfo {
+    pub sink_ref: SinkRef,
+}
+
+impl Command for GetSinkInfo {
+    const KIND: CommandKind = CommandKind::GetSinkInfo;
+}
+
+impl tag_struct::Put for GetSinkInfo {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.sink_ref);
+    }
+}
+
+pub struct GetSinkInfoList;
+
+impl Command for GetSinkInfoList {
+    const KIND: CommandKind = CommandKind::GetSinkInfoList;
+}
+
+impl tag_struct::Put for GetSinkInfoList {
+    fn put(self, _tag_struct: &mut TagStruct) {
+    }
+}
+
+pub struct SetDefaultSink {
+    pub name: String,
+}
+
+impl Command for SetDefaultSink {
+    const KIND: CommandKind = CommandKind::SetDefaultSink;
+}
+
+impl tag_struct::Put for SetDefaultSink {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_string(self.name);
+    }
+}
+
+/// Resolves a sink name, including special names like `@DEFAULT_SINK@`, to its index
+pub struct LookupSink {
+    pub name: String,
+}
+
+impl Command for LookupSink {
+    const KIND: CommandKind = CommandKind::LookupSink;
+}
+
+impl tag_struct::Put for LookupSink {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_string(self.name);
+    }
+}
+
+#[derive(Debug)]
+pub struct LookupSinkReply {
+    pub index: u32,
+}
+
+impl tag_struct::Pop for LookupSinkReply {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+        })
+    }
+}
+
+/// Since v11
+pub struct SuspendSink {
+    /// `None` suspends or resumes all sinks
+    pub sink_ref: Option<SinkRef>,
+    pub suspend: bool,
+}
+
+impl Command for SuspendSink {
+    const KIND: CommandKind = CommandKind::SuspendSink;
+}
+
+impl tag_struct::Put for SuspendSink {
+    fn put(self, tag_struct: &mut TagStruct) {
+        match self.sink_ref {
+            Some(sink_ref) => tag_struct.put(sink_ref),
+            // The server expects an empty name rather than a null one here
+            None => {
+                tag_struct.put_u32(INVALID_INDEX);
+                tag_struct.put_string(Some(String::new()));
+            },
+        }
+
+        tag_struct.put_bool(self.suspend);
+    }
+}
+
+/// Since v16
+pub struct SetSinkPort {
+    pub sink_ref: SinkRef,
+    pub port: String,
+}
+
+impl Command for SetSinkPort {
+    const KIND: CommandKind = CommandKind::SetSinkPort;
+}
+
+impl tag_struct::Put for SetSinkPort {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.sink_ref);
+        tag_struct.put_string(self.port);
+    }
+}
+
+#[derive(Debug)]
+pub struct SinkInfo {
+    pub index: u32,
+    pub name: String,
+    pub description: Option<String>,
+    pub sample_spec: SampleSpec,
+    pub channel_map: ChannelMap,
+    /// Index of the module that owns this sink
+    pub owner_module: Option<u32>,
+    pub volume: ChannelVolume,
+    pub mute: bool,
+    /// Index of the source monitoring this sink
+    pub monitor_source: Option<u32>,
+    pub monitor_source_name: Option<String>,
+    pub latency: Duration,
+    pub driver: Option<String>,
+    pub flags: SinkFlags,
+    /// Since v13
+    pub proplist: Option<Proplist>,
+    /// Latency requested by the connected streams. Since v13.
+    pub configured_latency: Option<Duration>,
+    /// Volume at which the hardware outputs at 0dB. Since v15.
+    pub base_volume: Option<u32>,
+    /// Since v15
+    pub state: Option<DeviceState>,
+    /// Number of discrete volume steps if `DECIBEL_VOLUME` is not set. Since v15.
+    pub n_volume_steps: Option<u32>,
+    /// Index of the card this sink belongs to. Since v15.
+    pub card: Option<u32>,
+    /// Empty before v16
+    pub ports: Vec<PortInfo>,
+    /// Since v16
+    pub active_port: Option<String>,
+    /// Formats supported by the sink. Empty before v21.
+    pub formats: Vec<FormatInfo>,
+}
+
+impl tag_struct::Pop for SinkInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+            name: tag_struct.pop_string()?.context("Missing name field")?,
+            description: tag_struct.pop_string()?,
+            sample_spec: tag_struct.pop_sample_spec()?,
+            channel_map: tag_struct.pop_channel_map()?,
+            owner_module: optional_index(tag_struct.pop_u32()?),
+            volume: tag_struct.pop_channel_volume()?,
+            mute: tag_struct.pop_bool()?,
+            monitor_source: optional_index(tag_struct.pop_u32()?),
+            monitor_source_name: tag_struct.pop_string()?,
+            latency: tag_struct.pop_usec()?,
+            driver: tag_struct.pop_string()?,
+            flags: SinkFlags(tag_struct.pop_u32()?),
+            proplist: since(version, 13, || tag_struct.pop_proplist())?,
+            configured_latency: since(version, 13, || tag_struct.pop_usec())?,
+            base_volume: since(version, 15, || tag_struct.pop_volume())?,
+            state: since(version, 15, || tag_struct.pop::<DeviceState>())?,
+            n_volume_steps: since(version, 15, || tag_struct.pop_u32())?,
+            card: since(version, 15, || tag_struct.pop_u32())?.and_then(optional_index),
+            ports: since(version, 16, || pop_ports(tag_struct))?.unwrap_or_default(),
+            active_port: since(version, 16, || tag_struct.pop_string())?.flatten(),
+            formats: since(version, 21, || pop_formats(tag_struct))?.unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SinkFlags(pub u32);
+
+impl SinkFlags {
+    /// Supports hardware volume control
+    pub const HW_VOLUME_CTRL: u32 = 0x0001;
+    /// Supports latency querying
+    pub const LATENCY: u32 = 0x0002;
+    /// Is a hardware sink of some kind, in contrast to "virtual"/software sinks
+    pub const HARDWARE: u32 = 0x0004;
+    /// Is a networked sink of some kind
+    pub const NETWORK: u32 = 0x0008;
+    /// Supports hardware mute control
+    pub const HW_MUTE_CTRL: u32 = 0x0010;
+    /// Volume can be translated to dB
+    pub const DECIBEL_VOLUME: u32 = 0x0020;
+    /// This sink is in flat volume mode
+    pub const FLAT_VOLUME: u32 = 0x0040;
+    /// The latency can be adjusted dynamically depending on the needs of the connected streams
+    pub const DYNAMIC_LATENCY: u32 = 0x0080;
+    /// The sink allows setting what formats are supported by the connected hardware
+    pub const SET_FORMATS: u32 = 0x0100;
+
+    pub fn contains(&self, flags: u32) -> bool {
+        self.0 & flags == flags
+    }
+}
+
+/// State of a sink or source
+#[derive(Debug, TryFromPrimitive, IntoPrimitive, Copy, Clone, PartialEq)]
+#[repr(u32)]
+pub enum DeviceState {
+    /// Running, connected to at least one stream
+    Running = 0,
+    /// Not running, but opened
+    Idle = 1,
+    /// Suspended, device closed
+    Suspended = 2,
+    /// The server could not determine the state
+    Invalid = u32::MAX,
+}
+
+impl tag_struct::Pop for DeviceState {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let state = tag_struct.pop_u32()?;
+        let state = Self::try_from_primitive(state)
+            .map_err(|_| anyhow!("Unknown device state {}", state))?;
+
+        Ok(state)
+    }
+}
+
+/// A port of a sink or source
+#[derive(Debug)]
+pub struct PortInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub priority: u32,
+    /// Since v24
+    pub available: Option<PortAvailable>,
+    /// Ports with the same group share their availability. Since v34.
+    pub availability_group: Option<String>,
+    /// Kind of port, e.g. speaker or headphones. Since v34.
+    pub port_type: Option<u32>,
+}
+
+impl tag_struct::Pop for PortInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            name: tag_struct.pop_string()?.context("Missing port name field")?,
+            description: tag_struct.pop_string()?,
+            priority: tag_struct.pop_u32()?,
+            available: since(version, 24, || tag_struct.pop::<PortAvailable>())?,
+            availability_group: since(version, 34, || tag_struct.pop_string())?.flatten(),
+            port_type: since(version, 34, || tag_struct.pop_u32())?,
+        })
+    }
+}
+
+#[derive(Debug, TryFromPrimitive, IntoPrimitive, Copy, Clone, PartialEq)]
+#[repr(u32)]
+pub enum PortAvailable {
+    /// This port does not support jack detection
+    Unknown = 0,
+    /// This port is not available, likely because the jack is not plugged in
+    No = 1,
+    /// This port is available, likely because the jack is plugged in
+    Yes = 2,
+}
+
+impl tag_struct::Pop for PortAvailable {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let available = tag_struct.pop_u32()?;
+        let available = Self::try_from_primitive(available)
+            .map_err(|_| anyhow!("Unknown port availability {}", available))?;
+
+        Ok(available)
+    }
+}
+
+pub(crate) fn pop_ports(tag_struct: &mut TagStruct) -> Result<Vec<PortInfo>> {
+    let num_ports = tag_struct.pop_u32()?;
+
+    (0..num_ports)
+        .map(|_| tag_struct.pop::<PortInfo>())
+        .collect()
+}
+
+pub(crate) fn pop_formats(tag_struct: &mut TagStruct) -> Result<Vec<FormatInfo>> {
+    let num_formats = tag_struct.pop_u8()?;
+
+    (0..num_formats)
+        .map(|_| tag_struct.pop_format_info())
+        .collect()
+}
@@ -0,0 +1,121 @@
+use anyhow::*;
+use crate::{tag_struct::{self, TagStruct, SampleSpec, ChannelMap, ChannelVolume}, proplist::Proplist, format::FormatInfo};
+use super::{Command, CommandKind, SinkRef, since, optional_index};
+use std::time::Duration;
+
+pub struct GetSinkInputInfo {
+    pub index: u32,
+}
+
+impl Command for GetSinkInputInfo {
+    const KIND: CommandKind = CommandKind::GetSinkInputInfo;
+}
+
+impl tag_struct::Put for GetSinkInputInfo {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+    }
+}
+
+pub struct GetSinkInputInfoList;
+
+impl Command for GetSinkInputInfoList {
+    const KIND: CommandKind = CommandKind::GetSinkInputInfoList;
+}
+
+impl tag_struct::Put for GetSinkInputInfoList {
+    fn put(self, _tag_struct: &mut TagStruct) {
+    }
+}
+
+/// Moves a sink input to another sink. Since v10.
+pub struct MoveSinkInput {
+    pub index: u32,
+    pub sink_ref: SinkRef,
+}
+
+impl Command for MoveSinkInput {
+    const KIND: CommandKind = CommandKind::MoveSinkInput;
+}
+
+impl tag_struct::Put for MoveSinkInput {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+        tag_struct.put(self.sink_ref);
+    }
+}
+
+pub struct KillSinkInput {
+    pub index: u32,
+}
+
+impl Command for KillSinkInput {
+    const KIND: CommandKind = CommandKind::KillSinkInput;
+}
+
+impl tag_struct::Put for KillSinkInput {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+    }
+}
+
+/// A playback stream connected to a sink
+#[derive(Debug)]
+pub struct SinkInputInfo {
+    pub index: u32,
+    pub name: Option<String>,
+    /// Index of the module that owns this sink input
+    pub owner_module: Option<u32>,
+    /// Index of the client that owns this sink input
+    pub client: Option<u32>,
+    /// Index of the sink this sink input is connected to
+    pub sink: u32,
+    pub sample_spec: SampleSpec,
+    pub channel_map: ChannelMap,
+    pub volume: ChannelVolume,
+    /// Latency due to buffering in the sink input
+    pub buffer_latency: Duration,
+    /// Latency of the sink device
+    pub sink_latency: Duration,
+    pub resample_method: Option<String>,
+    pub driver: Option<String>,
+    /// Since v11
+    pub mute: Option<bool>,
+    /// Since v13
+    pub proplist: Option<Proplist>,
+    /// Since v19
+    pub corked: Option<bool>,
+    /// Whether the stream has a volume at all. Passthrough streams don't. Since v20.
+    pub has_volume: Option<bool>,
+    /// Since v20
+    pub volume_writable: Option<bool>,
+    /// Since v21
+    pub format: Option<FormatInfo>,
+}
+
+impl tag_struct::Pop for SinkInputInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+            name: tag_struct.pop_string()?,
+            owner_module: optional_index(tag_struct.pop_u32()?),
+            client: optional_index(tag_struct.pop_u32()?),
+            sink: tag_struct.pop_u32()?,
+            sample_spec: tag_struct.pop_sample_spec()?,
+            channel_map: tag_struct.pop_channel_map()?,
+            volume: tag_struct.pop_channel_volume()?,
+            buffer_latency: tag_struct.pop_usec()?,
+            sink_latency: tag_struct.pop_usec()?,
+            resample_method: tag_struct.pop_string()?,
+            driver: tag_struct.pop_string()?,
+            mute: since(version, 11, || tag_struct.pop_bool())?,
+            proplist: since(version, 13, || tag_struct.pop_proplist())?,
+            corked: since(version, 19, || tag_struct.pop_bool())?,
+            has_volume: since(version, 20, || tag_struct.pop_bool())?,
+            volume_writable: since(version, 20, || tag_struct.pop_bool())?,
+            format: since(version, 21, || tag_struct.pop_format_info())?,
+        })
+    }
+}
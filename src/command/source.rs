@@ -0,0 +1,215 @@
+use anyhow::*;
+use crate::{INVALID_INDEX, tag_struct::{self, TagStruct, SampleSpec, ChannelMap, ChannelVolume}, proplist::Proplist, format::FormatInfo};
+use super::{Command, CommandKind, SourceRef, DeviceState, PortInfo, since, optional_index};
+use super::sink::{pop_ports, pop_formats};
+use std::time::Duration;
+
+pub struct GetSourceInfo {
+    pub source_ref: SourceRef,
+}
+
+impl Command for GetSourceInfo {
+    const KIND: CommandKind = CommandKind::GetSourceInfo;
+}
+
+impl tag_struct::Put for GetSourceInfo {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.source_ref);
+    }
+}
+
+pub struct GetSourceInfoList;
+
+impl Command for GetSourceInfoList {
+    const KIND: CommandKind = CommandKind::GetSourceInfoList;
+}
+
+impl tag_struct::Put for GetSourceInfoList {
+    fn put(self, _tag_struct: &mut TagStruct) {
+    }
+}
+
+pub struct SetDefaultSource {
+    pub name: String,
+}
+
+impl Command for SetDefaultSource {
+    const KIND: CommandKind = CommandKind::SetDefaultSource;
+}
+
+impl tag_struct::Put for SetDefaultSource {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_string(self.name);
+    }
+}
+
+/// Resolves a source name, including special names like `@DEFAULT_SOURCE@`, to its index
+pub struct LookupSource {
+    pub name: String,
+}
+
+impl Command for LookupSource {
+    const KIND: CommandKind = CommandKind::LookupSource;
+}
+
+impl tag_struct::Put for LookupSource {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_string(self.name);
+    }
+}
+
+#[derive(Debug)]
+pub struct LookupSourceReply {
+    pub index: u32,
+}
+
+impl tag_struct::Pop for LookupSourceReply {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+        })
+    }
+}
+
+/// Since v11
+pub struct SuspendSource {
+    /// `None` suspends or resumes all sources
+    pub source_ref: Option<SourceRef>,
+    pub suspend: bool,
+}
+
+impl Command for SuspendSource {
+    const KIND: CommandKind = CommandKind::SuspendSource;
+}
+
+impl tag_struct::Put for SuspendSource {
+    fn put(self, tag_struct: &mut TagStruct) {
+        match self.source_ref {
+            Some(source_ref) => tag_struct.put(source_ref),
+            // The server expects an empty name rather than a null one here
+            None => {
+                tag_struct.put_u32(INVALID_INDEX);
+                tag_struct.put_string(Some(String::new()));
+            },
+        }
+
+        tag_struct.put_bool(self.suspend);
+    }
+}
+
+/// Since v16
+pub struct SetSourcePort {
+    pub source_ref: SourceRef,
+    pub port: String,
+}
+
+impl Command for SetSourcePort {
+    const KIND: CommandKind = CommandKind::SetSourcePort;
+}
+
+impl tag_struct::Put for SetSourcePort {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.source_ref);
+        tag_struct.put_string(self.port);
+    }
+}
+
+#[derive(Debug)]
+pub struct SourceInfo {
+    pub index: u32,
+    pub name: String,
+    pub description: Option<String>,
+    pub sample_spec: SampleSpec,
+    pub channel_map: ChannelMap,
+    /// Index of the module that owns this source
+    pub owner_module: Option<u32>,
+    pub volume: ChannelVolume,
+    pub mute: bool,
+    /// If this is a monitor source, the index of the sink it monitors
+    pub monitor_of_sink: Option<u32>,
+    pub monitor_of_sink_name: Option<String>,
+    pub latency: Duration,
+    pub driver: Option<String>,
+    pub flags: SourceFlags,
+    /// Since v13
+    pub proplist: Option<Proplist>,
+    /// Latency requested by the connected streams. Since v13.
+    pub configured_latency: Option<Duration>,
+    /// Volume at which the hardware records at 0dB. Since v15.
+    pub base_volume: Option<u32>,
+    /// Since v15
+    pub state: Option<DeviceState>,
+    /// Number of discrete volume steps if `DECIBEL_VOLUME` is not set. Since v15.
+    pub n_volume_steps: Option<u32>,
+    /// Index of the card this source belongs to. Since v15.
+    pub card: Option<u32>,
+    /// Empty before v16
+    pub ports: Vec<PortInfo>,
+    /// Since v16
+    pub active_port: Option<String>,
+    /// Formats supported by the source. Empty before v22.
+    pub formats: Vec<FormatInfo>,
+}
+
+impl SourceInfo {
+    pub fn is_monitor(&self) -> bool {
+        self.monitor_of_sink.is_some()
+    }
+}
+
+impl tag_struct::Pop for SourceInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+            name: tag_struct.pop_string()?.context("Missing name field")?,
+            description: tag_struct.pop_string()?,
+            sample_spec: tag_struct.pop_sample_spec()?,
+            channel_map: tag_struct.pop_channel_map()?,
+            owner_module: optional_index(tag_struct.pop_u32()?),
+            volume: tag_struct.pop_channel_volume()?,
+            mute: tag_struct.pop_bool()?,
+            monitor_of_sink: optional_index(tag_struct.pop_u32()?),
+            monitor_of_sink_name: tag_struct.pop_string()?,
+            latency: tag_struct.pop_usec()?,
+            driver: tag_struct.pop_string()?,
+            flags: SourceFlags(tag_struct.pop_u32()?),
+            proplist: since(version, 13, || tag_struct.pop_proplist())?,
+            configured_latency: since(version, 13, || tag_struct.pop_usec())?,
+            base_volume: since(version, 15, || tag_struct.pop_volume())?,
+            state: since(version, 15, || tag_struct.pop::<DeviceState>())?,
+            n_volume_steps: since(version, 15, || tag_struct.pop_u32())?,
+            card: since(version, 15, || tag_struct.pop_u32())?.and_then(optional_index),
+            ports: since(version, 16, || pop_ports(tag_struct))?.unwrap_or_default(),
+            active_port: since(version, 16, || tag_struct.pop_string())?.flatten(),
+            formats: since(version, 22, || pop_formats(tag_struct))?.unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SourceFlags(pub u32);
+
+impl SourceFlags {
+    /// Supports hardware volume control
+    pub const HW_VOLUME_CTRL: u32 = 0x0001;
+    /// Supports latency querying
+    pub const LATENCY: u32 = 0x0002;
+    /// Is a hardware source of some kind, in contrast to "virtual"/software sources
+    pub const HARDWARE: u32 = 0x0004;
+    /// Is a networked source of some kind
+    pub const NETWORK: u32 = 0x0008;
+    /// Supports hardware mute control
+    pub const HW_MUTE_CTRL: u32 = 0x0010;
+    /// Volume can be translated to dB
+    pub const DECIBEL_VOLUME: u32 = 0x0020;
+    /// The latency can be adjusted dynamically depending on the needs of the connected streams
+    pub const DYNAMIC_LATENCY: u32 = 0x0040;
+    /// This source is in flat volume mode
+    pub const FLAT_VOLUME: u32 = 0x0080;
+
+    pub fn contains(&self, flags: u32) -> bool {
+        self.0 & flags == flags
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::*;
+use crate::{tag_struct::{self, TagStruct, SampleSpec, ChannelMap, ChannelVolume}, proplist::Proplist, format::FormatInfo};
+use super::{Command, CommandKind, SourceRef, since, optional_index};
+use std::time::Duration;
+
+pub struct GetSourceOutputInfo {
+    pub index: u32,
+}
+
+impl Command for GetSourceOutputInfo {
+    const KIND: CommandKind = CommandKind::GetSourceOutputInfo;
+}
+
+impl tag_struct::Put for GetSourceOutputInfo {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+    }
+}
+
+pub struct GetSourceOutputInfoList;
+
+impl Command for GetSourceOutputInfoList {
+    const KIND: CommandKind = CommandKind::GetSourceOutputInfoList;
+}
+
+impl tag_struct::Put for GetSourceOutputInfoList {
+    fn put(self, _tag_struct: &mut TagStruct) {
+    }
+}
+
+/// Moves a source output to another source. Since v10.
+pub struct MoveSourceOutput {
+    pub index: u32,
+    pub source_ref: SourceRef,
+}
+
+impl Command for MoveSourceOutput {
+    const KIND: CommandKind = CommandKind::MoveSourceOutput;
+}
+
+impl tag_struct::Put for MoveSourceOutput {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+        tag_struct.put(self.source_ref);
+    }
+}
+
+pub struct KillSourceOutput {
+    pub index: u32,
+}
+
+impl Command for KillSourceOutput {
+    const KIND: CommandKind = CommandKind::KillSourceOutput;
+}
+
+impl tag_struct::Put for KillSourceOutput {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+    }
+}
+
+/// A record stream connected to a source
+#[derive(Debug)]
+pub struct SourceOutputInfo {
+    pub index: u32,
+    pub name: Option<String>,
+    /// Index of the module that owns this source output
+    pub owner_module: Option<u32>,
+    /// Index of the client that owns this source output
+    pub client: Option<u32>,
+    /// Index of the source this source output is connected to
+    pub source: u32,
+    pub sample_spec: SampleSpec,
+    pub channel_map: ChannelMap,
+    /// Latency due to buffering in the source output
+    pub buffer_latency: Duration,
+    /// Latency of the source device
+    pub source_latency: Duration,
+    pub resample_method: Option<String>,
+    pub driver: Option<String>,
+    /// Since v13
+    pub proplist: Option<Proplist>,
+    /// Since v19
+    pub corked: Option<bool>,
+    /// Since v22
+    pub volume: Option<ChannelVolume>,
+    /// Since v22
+    pub mute: Option<bool>,
+    /// Whether the stream has a volume at all. Passthrough streams don't. Since v22.
+    pub has_volume: Option<bool>,
+    /// Since v22
+    pub volume_writable: Option<bool>,
+    /// Since v22
+    pub format: Option<FormatInfo>,
+}
+
+impl tag_struct::Pop for SourceOutputInfo {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let version = tag_struct.protocol_version();
+
+        Ok(Self {
+            index: tag_struct.pop_u32().context("Missing index field")?,
+            name: tag_struct.pop_string()?,
+            owner_module: optional_index(tag_struct.pop_u32()?),
+            client: optional_index(tag_struct.pop_u32()?),
+            source: tag_struct.pop_u32()?,
+            sample_spec: tag_struct.pop_sample_spec()?,
+            channel_map: tag_struct.pop_channel_map()?,
+            buffer_latency: tag_struct.pop_usec()?,
+            source_latency: tag_struct.pop_usec()?,
+            resample_method: tag_struct.pop_string()?,
+            driver: tag_struct.pop_string()?,
+            proplist: since(version, 13, || tag_struct.pop_proplist())?,
+            corked: since(version, 19, || tag_struct.pop_bool())?,
+            volume: since(version, 22, || tag_struct.pop_channel_volume())?,
+            mute: since(version, 22, || tag_struct.pop_bool())?,
+            has_volume: since(version, 22, || tag_struct.pop_bool())?,
+            volume_writable: since(version, 22, || tag_struct.pop_bool())?,
+            format: since(version, 22, || tag_struct.pop_format_info())?,
+        })
+    }
+}
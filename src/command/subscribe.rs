@@ -0,0 +1,108 @@
+use anyhow::*;
+use num_enum::{TryFromPrimitive, IntoPrimitive};
+use crate::tag_struct::{self, TagStruct};
+use super::{Command, CommandKind};
+
+/// Sets which events the server sends for this connection
+pub struct Subscribe {
+    pub mask: SubscriptionMask,
+}
+
+impl Command for Subscribe {
+    const KIND: CommandKind = CommandKind::Subscribe;
+}
+
+impl tag_struct::Put for Subscribe {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.mask.0);
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SubscriptionMask(pub u32);
+
+impl SubscriptionMask {
+    pub const NULL: u32 = 0x0000;
+    pub const SINK: u32 = 0x0001;
+    pub const SOURCE: u32 = 0x0002;
+    pub const SINK_INPUT: u32 = 0x0004;
+    pub const SOURCE_OUTPUT: u32 = 0x0008;
+    pub const MODULE: u32 = 0x0010;
+    pub const CLIENT: u32 = 0x0020;
+    pub const SAMPLE_CACHE: u32 = 0x0040;
+    pub const SERVER: u32 = 0x0080;
+    /// Obsolete, autoloading was removed from the server
+    pub const AUTOLOAD: u32 = 0x0100;
+    pub const CARD: u32 = 0x0200;
+    pub const ALL: u32 = 0x02ff;
+
+    pub fn contains(&self, flags: u32) -> bool {
+        self.0 & flags == flags
+    }
+
+    /// Whether events of the given facility are selected
+    pub fn matches(&self, facility: Facility) -> bool {
+        self.0 & (1 << u8::from(facility)) != 0
+    }
+}
+
+/// Kind of object a subscription event is about
+#[derive(Debug, TryFromPrimitive, IntoPrimitive, Copy, Clone, PartialEq)]
+#[repr(u8)]
+pub enum Facility {
+    Sink = 0,
+    Source = 1,
+    SinkInput = 2,
+    SourceOutput = 3,
+    Module = 4,
+    Client = 5,
+    SampleCache = 6,
+    /// Global server change, only sent with `SubscriptionEventType::Change`
+    Server = 7,
+    /// Obsolete
+    Autoload = 8,
+    Card = 9,
+}
+
+#[derive(Debug, TryFromPrimitive, IntoPrimitive, Copy, Clone, PartialEq)]
+#[repr(u8)]
+pub enum SubscriptionEventType {
+    /// An object was added
+    New = 0x00,
+    /// An object was modified
+    Change = 0x10,
+    /// An object was removed
+    Remove = 0x20,
+}
+
+const FACILITY_MASK: u32 = 0x0F;
+const TYPE_MASK: u32 = 0x30;
+
+/// Sent by the server as `SubscribeEvent` for every change selected by the subscription mask
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SubscriptionEvent {
+    pub facility: Facility,
+    pub event_type: SubscriptionEventType,
+    /// Index of the affected object
+    pub index: u32,
+}
+
+impl tag_struct::Pop for SubscriptionEvent {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let event = tag_struct.pop_u32().context("Missing event type field")?;
+
+        let facility = (event & FACILITY_MASK) as u8;
+        let facility = Facility::try_from_primitive(facility)
+            .map_err(|_| anyhow!("Unknown subscription facility {}", facility))?;
+
+        let event_type = (event & TYPE_MASK) as u8;
+        let event_type = SubscriptionEventType::try_from_primitive(event_type)
+            .map_err(|_| anyhow!("Unknown subscription event type {:#x}", event_type))?;
+
+        Ok(Self {
+            facility,
+            event_type,
+            index: tag_struct.pop_u32().context("Missing index field")?,
+        })
+    }
+}
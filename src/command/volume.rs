@@ -0,0 +1,133 @@
+use crate::tag_struct::{self, TagStruct, ChannelVolume};
+use super::{Command, CommandKind, SinkRef, SourceRef};
+
+pub struct SetSinkVolume {
+    pub sink_ref: SinkRef,
+    pub volume: ChannelVolume,
+}
+
+impl Command for SetSinkVolume {
+    const KIND: CommandKind = CommandKind::SetSinkVolume;
+}
+
+impl tag_struct::Put for SetSinkVolume {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.sink_ref);
+        tag_struct.put_channel_volume(self.volume);
+    }
+}
+
+pub struct SetSourceVolume {
+    pub source_ref: SourceRef,
+    pub volume: ChannelVolume,
+}
+
+impl Command for SetSourceVolume {
+    const KIND: CommandKind = CommandKind::SetSourceVolume;
+}
+
+impl tag_struct::Put for SetSourceVolume {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.source_ref);
+        tag_struct.put_channel_volume(self.volume);
+    }
+}
+
+pub struct SetSinkInputVolume {
+    pub index: u32,
+    pub volume: ChannelVolume,
+}
+
+impl Command for SetSinkInputVolume {
+    const KIND: CommandKind = CommandKind::SetSinkInputVolume;
+}
+
+impl tag_struct::Put for SetSinkInputVolume {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+        tag_struct.put_channel_volume(self.volume);
+    }
+}
+
+/// Since v22
+pub struct SetSourceOutputVolume {
+    pub index: u32,
+    pub volume: ChannelVolume,
+}
+
+impl Command for SetSourceOutputVolume {
+    const KIND: CommandKind = CommandKind::SetSourceOutputVolume;
+}
+
+impl tag_struct::Put for SetSourceOutputVolume {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+        tag_struct.put_channel_volume(self.volume);
+    }
+}
+
+pub struct SetSinkMute {
+    pub sink_ref: SinkRef,
+    pub mute: bool,
+}
+
+impl Command for SetSinkMute {
+    const KIND: CommandKind = CommandKind::SetSinkMute;
+}
+
+impl tag_struct::Put for SetSinkMute {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.sink_ref);
+        tag_struct.put_bool(self.mute);
+    }
+}
+
+pub struct SetSourceMute {
+    pub source_ref: SourceRef,
+    pub mute: bool,
+}
+
+impl Command for SetSourceMute {
+    const KIND: CommandKind = CommandKind::SetSourceMute;
+}
+
+impl tag_struct::Put for SetSourceMute {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put(self.source_ref);
+        tag_struct.put_bool(self.mute);
+    }
+}
+
+/// Since v11
+pub struct SetSinkInputMute {
+    pub index: u32,
+    pub mute: bool,
+}
+
+impl Command for SetSinkInputMute {
+    const KIND: CommandKind = CommandKind::SetSinkInputMute;
+}
+
+impl tag_struct::Put for SetSinkInputMute {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+        tag_struct.put_bool(self.mute);
+    }
+}
+
+/// Since v22
+pub struct SetSourceOutputMute {
+    pub index: u32,
+    pub mute: bool,
+}
+
+impl Command for SetSourceOutputMute {
+    const KIND: CommandKind = CommandKind::SetSourceOutputMute;
+}
+
+impl tag_struct::Put for SetSourceOutputMute {
+    fn put(self, tag_struct: &mut TagStruct) {
+        tag_struct.put_u32(self.index);
+        tag_struct.put_bool(self.mute);
+    }
+}
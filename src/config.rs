@@ -0,0 +1,203 @@
+use anyhow::*;
+use std::{env, ffi::OsStr, io, path::{Path, PathBuf}};
+use tokio::fs;
+use futures::prelude::*;
+use crate::cookie::Cookie;
+
+/// Path of the system wide client configuration
+pub const SYSTEM_CONFIG_PATH: &str = "/etc/pulse/client.conf";
+
+/// Client settings as read from `client.conf`.
+///
+/// See `pulse-client.conf(5)` for the meaning of the individual settings.
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Server(s) to connect to, in the same format as `PULSE_SERVER`
+    pub default_server: Option<String>,
+    /// Sink new playback streams are connected to
+    pub default_sink: Option<String>,
+    /// Source new record streams are connected to
+    pub default_source: Option<String>,
+    /// File to read the authentication cookie from
+    pub cookie_file: Option<PathBuf>,
+    /// Authentication cookie to use instead of looking one up. Only `PULSE_COOKIE` takes precedence.
+    /// Not read from `client.conf`.
+    pub cookie: Option<Cookie>,
+    pub autospawn: bool,
+    pub enable_shm: bool,
+    pub enable_memfd: bool,
+    pub shm_size_bytes: usize,
+    /// Try connecting to localhost via TCP if no local socket is reachable
+    pub auto_connect_localhost: bool,
+    pub auto_connect_display: bool,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            default_server: None,
+            default_sink: None,
+            default_source: None,
+            cookie_file: None,
+            cookie: None,
+            autospawn: true,
+            enable_shm: true,
+            enable_memfd: true,
+            shm_size_bytes: 0,
+            auto_connect_localhost: false,
+            auto_connect_display: false,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Loads the configuration like libpulse does and applies environment overrides.
+    ///
+    /// Only the first existing file out of `PULSE_CLIENTCONFIG`, the user's `client.conf`
+    /// and the system wide configuration is read. If `PULSE_CLIENTCONFIG` is set no other file is considered.
+    /// The file is followed by the `*.conf` files in its `.d` directory in lexical order.
+    pub async fn load() -> Result<Self> {
+        let mut config = Self::default();
+
+        let paths = match env::var_os("PULSE_CLIENTCONFIG") {
+            Some(path) => vec![PathBuf::from(path)],
+            None => {
+                let mut paths = Vec::new();
+
+                if let Some(config_dir) = dirs::config_dir() {
+                    paths.push(config_dir.join("pulse/client.conf"));
+                }
+
+                paths.push(PathBuf::from(SYSTEM_CONFIG_PATH));
+
+                paths
+            },
+        };
+
+        for path in paths {
+            if fs::metadata(&path).await.is_err() {
+                continue;
+            }
+
+            config.load_file(&path).await?;
+
+            let mut dot_d = path.into_os_string();
+            dot_d.push(".d");
+            config.load_dir(Path::new(&dot_d)).await?;
+
+            break;
+        }
+
+        config.apply_env();
+
+        Ok(config)
+    }
+
+    /// Applies the settings from the given file. Missing files are ignored.
+    pub async fn load_file(&mut self, path: &Path) -> Result<()> {
+        let contents = match fs::read_to_string(path).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        self.parse(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    async fn load_dir(&mut self, dir: &Path) -> Result<()> {
+        let entries = match fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err).with_context(|| format!("Failed to read {}", dir.display())),
+        };
+
+        let mut paths = entries
+            .map_ok(|entry| entry.path())
+            .try_filter(|path| future::ready(path.extension() == Some(OsStr::new("conf"))))
+            .try_collect::<Vec<_>>().await
+            .with_context(|| format!("Failed to read {}", dir.display()))?;
+        paths.sort();
+
+        for path in paths {
+            self.load_file(&path).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies the settings from the contents of a `client.conf` file.
+    pub fn parse(&mut self, contents: &str) -> Result<()> {
+        for (line_number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') || line.starts_with('[') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or_default().trim();
+            let value = parts.next()
+                .with_context(|| format!("Missing '=' on line {}", line_number + 1))?
+                .trim();
+
+            self.set(key, value)
+                .with_context(|| format!("Invalid value for {} on line {}", key, line_number + 1))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets a single setting by its `client.conf` key. Unknown keys are ignored.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "default-server" => self.default_server = non_empty(value),
+            "default-sink" => self.default_sink = non_empty(value),
+            "default-source" => self.default_source = non_empty(value),
+            "cookie-file" => self.cookie_file = non_empty(value).map(PathBuf::from),
+            "autospawn" => self.autospawn = parse_bool(value)?,
+            "enable-shm" => self.enable_shm = parse_bool(value)?,
+            "enable-memfd" => self.enable_memfd = parse_bool(value)?,
+            "shm-size-bytes" => self.shm_size_bytes = value.parse()?,
+            "auto-connect-localhost" => self.auto_connect_localhost = parse_bool(value)?,
+            "auto-connect-display" => self.auto_connect_display = parse_bool(value)?,
+            _ => {},
+        }
+
+        Ok(())
+    }
+
+    /// Overrides settings with the `PULSE_SERVER`, `PULSE_SINK` and `PULSE_SOURCE`
+    /// environment variables, if set. `PULSE_COOKIE` is handled by `Cookie::load`.
+    pub fn apply_env(&mut self) {
+        if let Some(server) = env_var("PULSE_SERVER") {
+            self.default_server = Some(server);
+        }
+
+        if let Some(sink) = env_var("PULSE_SINK") {
+            self.default_sink = Some(sink);
+        }
+
+        if let Some(source) = env_var("PULSE_SOURCE") {
+            self.default_source = Some(source);
+        }
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().and_then(|value| non_empty(&value))
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    Some(value.trim())
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+}
+
+fn parse_bool(value: &str) -> Result<bool> {
+    Ok(match value.to_ascii_lowercase().as_str() {
+        "1" | "y" | "t" | "yes" | "true" | "on" => true,
+        "0" | "n" | "f" | "no" | "false" | "off" => false,
+        _ => bail!("Invalid boolean {:?}", value),
+    })
+}
@@ -0,0 +1,107 @@
+use anyhow::*;
+use std::{env, fmt, path::Path};
+use tokio::fs;
+use crate::config::ClientConfig;
+
+/// Length of an authentication cookie as required by the protocol
+pub const COOKIE_LENGTH: usize = 256;
+
+/// Authentication cookie sent to the server during the handshake
+#[derive(Clone, PartialEq)]
+pub struct Cookie(Vec<u8>);
+
+impl Cookie {
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Result<Self> {
+        let bytes = bytes.into();
+
+        ensure!(
+            bytes.len() == COOKIE_LENGTH,
+            "Cookie must be {} bytes long, got {}", COOKIE_LENGTH, bytes.len()
+        );
+
+        Ok(Self(bytes))
+    }
+
+    /// Parses a cookie from its hexadecimal representation
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let hex = hex.trim();
+
+        ensure!(hex.is_ascii(), "Cookie is not valid hex");
+        ensure!(hex.len() % 2 == 0, "Cookie hex has odd length");
+
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .context("Cookie is not valid hex")?;
+
+        Self::from_bytes(bytes)
+    }
+
+    /// An all-zero cookie, for servers that authenticate by other means (e.g. credentials)
+    pub fn zeroed() -> Self {
+        Self(vec![0; COOKIE_LENGTH])
+    }
+
+    pub async fn load_file(path: &Path) -> Result<Self> {
+        let cookie = fs::read(path).await
+            .with_context(|| format!("Failed to read cookie from {}", path.display()))?;
+
+        Self::from_bytes(cookie)
+            .with_context(|| format!("Invalid cookie in {}", path.display()))
+    }
+
+    /// Looks up the cookie in the same order as libpulse:
+    ///
+    /// 1. `PULSE_COOKIE`, either as hex data or as path to a cookie file
+    /// 2. The cookie set in `ClientConfig::cookie`
+    /// 3. The configured `cookie-file`
+    /// 4. `~/.config/pulse/cookie`
+    /// 5. The legacy `~/.pulse-cookie`
+    ///
+    /// Like libpulse, sources that are missing or invalid are skipped.
+    /// If none of them yield a cookie a zeroed cookie is returned.
+    pub async fn load(config: &ClientConfig) -> Self {
+        if let Some(value) = env::var("PULSE_COOKIE").ok().filter(|value| !value.is_empty()) {
+            if let Ok(cookie) = Self::from_hex(&value) {
+                return cookie;
+            }
+
+            if let Ok(cookie) = Self::load_file(Path::new(&value)).await {
+                return cookie;
+            }
+        }
+
+        if let Some(cookie) = &config.cookie {
+            return cookie.clone();
+        }
+
+        let paths = vec![
+            config.cookie_file.clone(),
+            dirs::config_dir().map(|dir| dir.join("pulse/cookie")),
+            dirs::home_dir().map(|dir| dir.join(".pulse-cookie")),
+        ];
+
+        for path in paths.into_iter().flatten() {
+            if let Ok(cookie) = Self::load_file(&path).await {
+                return cookie;
+            }
+        }
+
+        Self::zeroed()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl fmt::Debug for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Cookie(..)")
+    }
+}
@@ -1,8 +1,9 @@
 use num_enum::{TryFromPrimitive, IntoPrimitive};
 use anyhow::*;
 use crate::tag_struct::{self, TagStruct};
+use std::fmt;
 
-#[derive(Debug, TryFromPrimitive, IntoPrimitive, Copy, Clone)]
+#[derive(Debug, TryFromPrimitive, IntoPrimitive, Copy, Clone, PartialEq)]
 #[repr(u32)]
 pub enum ErrorKind {
     /// No error
@@ -69,3 +70,52 @@ impl tag_struct::Pop for ErrorKind {
         Ok(error_kind)
     }
 }
+
+impl ErrorKind {
+    /// Returns the server error behind `error`, if it was caused by an error reply
+    pub fn from_error(error: &Error) -> Option<Self> {
+        error.downcast_ref::<Self>().copied()
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Ok => "No error",
+            Self::Access => "Access failure",
+            Self::Command => "Unknown command",
+            Self::Invalid => "Invalid argument",
+            Self::Exist => "Entity exists",
+            Self::NoEntity => "No such entity",
+            Self::ConnectionRefused => "Connection refused",
+            Self::Protocol => "Protocol error",
+            Self::Timeout => "Timeout",
+            Self::AuthKey => "No authentication key",
+            Self::Internal => "Internal error",
+            Self::ConnectionTerminated => "Connection terminated",
+            Self::Killed => "Entity killed",
+            Self::InvalidServer => "Invalid server",
+            Self::ModInitFailed => "Module initialization failed",
+            Self::BadState => "Bad state",
+            Self::NoData => "No data",
+            Self::Version => "Incompatible protocol version",
+            Self::TooLarge => "Data too large",
+            Self::NotSupported => "Operation not supported",
+            Self::Unknown => "The error code was unknown to the client",
+            Self::NoExtension => "Extension does not exist",
+            Self::Obsolete => "Obsolete functionality",
+            Self::NotImplemented => "Missing implementation",
+            Self::Forked => "The caller forked without calling execve() and tried to reuse the context",
+            Self::Io => "An IO error happened",
+            Self::Busy => "Device or resource busy",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Server error replies are returned as `ErrorKind` and can be
+/// recovered from an `anyhow::Error` with `downcast_ref::<ErrorKind>()`.
+impl std::error::Error for ErrorKind {}
@@ -0,0 +1,86 @@
+use num_enum::{TryFromPrimitive, IntoPrimitive};
+use crate::{proplist::{keys, Proplist}, sample::SampleFormat};
+
+#[derive(TryFromPrimitive, IntoPrimitive, Debug, Copy, Clone, PartialEq)]
+#[repr(u8)]
+pub enum Encoding {
+    /// Any encoding format, PCM or compressed
+    Any,
+    /// Any PCM format
+    PCM,
+    /// AC3 data encapsulated in IEC 61937 header/padding
+    AC3,
+    /// EAC3 data encapsulated in IEC 61937 header/padding
+    EAC3,
+    /// MPEG-1 or MPEG-2 (Part 3, not AAC) data encapsulated in IEC 61937 header/padding
+    MPEG,
+    /// DTS data encapsulated in IEC 61937 header/padding
+    DTS,
+    /// MPEG-2 AAC data encapsulated in IEC 61937 header/padding. \since 4.0
+    MPEG2AAC,
+    /// Dolby TrueHD data encapsulated in IEC 61937 header/padding. \since 13.0
+    TrueHD,
+    /// DTS-HD Master Audio encapsulated in IEC 61937 header/padding. \since 13.0
+    DTSHD,
+    /// Valid encoding types must be less than this value
+    MAX,
+    /// Represents an invalid encoding
+    INVALID = u8::MAX,
+}
+
+/// A stream format, which may be PCM or a compressed format for passthrough
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatInfo {
+    pub encoding: Encoding,
+    /// Format details such as `format.rate` and `format.channels`
+    pub proplist: Proplist,
+}
+
+impl FormatInfo {
+    pub fn new(encoding: Encoding) -> Self {
+        Self {
+            encoding,
+            proplist: Proplist::new(),
+        }
+    }
+
+    pub fn pcm() -> Self {
+        Self::new(Encoding::PCM)
+    }
+
+    pub fn is_pcm(&self) -> bool {
+        self.encoding == Encoding::PCM
+    }
+
+    /// Sets `format.rate`. Property values are JSON, like libpulse expects them.
+    pub fn set_rate(&mut self, rate: u32) {
+        self.proplist.set_str(keys::FORMAT_RATE, &rate.to_string());
+    }
+
+    /// Sets `format.channels`
+    pub fn set_channels(&mut self, channels: u8) {
+        self.proplist.set_str(keys::FORMAT_CHANNELS, &channels.to_string());
+    }
+
+    /// Sets `format.sample_format`. Only meaningful for PCM.
+    pub fn set_sample_format(&mut self, format: SampleFormat) {
+        let name = match format {
+            SampleFormat::U8 => "u8",
+            SampleFormat::ALAW => "aLaw",
+            SampleFormat::ULAW => "uLaw",
+            SampleFormat::S16LE => "s16le",
+            SampleFormat::S16BE => "s16be",
+            SampleFormat::FLOAT32LE => "float32le",
+            SampleFormat::FLOAT32BE => "float32be",
+            SampleFormat::S32LE => "s32le",
+            SampleFormat::S32BE => "s32be",
+            SampleFormat::S24LE => "s24le",
+            SampleFormat::S24BE => "s24be",
+            SampleFormat::S24_32LE => "s24-32le",
+            SampleFormat::S24_32BE => "s24-32be",
+            SampleFormat::MAX | SampleFormat::INVALID => "invalid",
+        };
+
+        self.proplist.set_str(keys::FORMAT_SAMPLE_FORMAT, &format!("\"{}\"", name));
+    }
+}
@@ -1,5 +1,10 @@
 pub use crate::{
     client::Client,
+    server_address::ServerAddress,
+    config::ClientConfig,
+    cookie::Cookie,
+    proplist::Proplist,
+    module_args::ModuleArguments,
 };
 
 pub mod broker;
@@ -11,7 +16,22 @@ pub mod frame;
 pub mod sample;
 pub mod channel;
 pub mod error;
+pub mod server_address;
+pub mod config;
+pub mod cookie;
+pub mod proplist;
+pub mod format;
+pub mod module_args;
 
 pub const VOLUME_NORMAL: u32 = 0x10000;
-pub const PROTOCOL_VERSION: u32 = 8;
+pub const VOLUME_MUTED: u32 = 0;
+/// Largest valid volume value
+pub const VOLUME_MAX: u32 = u32::MAX / 2;
+/// Special value meaning "use the default volume"
+pub const VOLUME_INVALID: u32 = u32::MAX;
+/// Highest protocol version this crate can speak.
+/// The version actually used is negotiated during authentication, see `Client::protocol_version`.
+pub const PROTOCOL_VERSION: u32 = 35;
+/// Oldest protocol version supported by this crate
+pub const MIN_PROTOCOL_VERSION: u32 = 8;
 pub const INVALID_INDEX: u32 = u32::MAX;
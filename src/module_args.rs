@@ -0,0 +1,104 @@
+use anyhow::*;
+use std::fmt;
+
+/// Arguments for `Client::load_module`, formatted as `key=value` pairs.
+///
+/// Values containing whitespace, quotes or backslashes are double quoted and escaped,
+/// so they reach the module unchanged.
+///
+/// ```no_run
+/// # use repulse::ModuleArguments;
+/// # fn main() -> anyhow::Result<()> {
+/// let args = ModuleArguments::new()
+///     .arg("sink_name", "recording")?
+///     .arg("rate", 48000)?
+///     .arg("sink_properties", "device.description=\"Recording Sink\"")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleArguments {
+    args: Vec<(String, String)>,
+}
+
+impl ModuleArguments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an argument, replacing an earlier one with the same key.
+    ///
+    /// Fails if the key is empty or contains whitespace, `=` or quotes.
+    pub fn arg(mut self, key: impl Into<String>, value: impl ToString) -> Result<Self> {
+        self.set(key, value)?;
+        Ok(self)
+    }
+
+    /// Like `arg`, but by reference
+    pub fn set(&mut self, key: impl Into<String>, value: impl ToString) -> Result<()> {
+        let key = key.into();
+        let value = value.to_string();
+
+        ensure!(is_valid_key(&key), "Invalid module argument key {:?}", key);
+
+        match self.args.iter_mut().find(|(existing, _)| *existing == key) {
+            Some(arg) => arg.1 = value,
+            None => self.args.push((key, value)),
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.args.iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && !key.contains(|c: char| c.is_whitespace() || c == '=' || c == '"' || c == '\'')
+}
+
+fn needs_quoting(value: &str) -> bool {
+    value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'' || c == '\\')
+}
+
+impl fmt::Display for ModuleArguments {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (key, value)) in self.args.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+
+            if !needs_quoting(value) {
+                write!(f, "{}={}", key, value)?;
+                continue;
+            }
+
+            write!(f, "{}=\"", key)?;
+
+            for c in value.chars() {
+                if c == '"' || c == '\\' {
+                    f.write_str("\\")?;
+                }
+
+                write!(f, "{}", c)?;
+            }
+
+            f.write_str("\"")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<ModuleArguments> for String {
+    fn from(args: ModuleArguments) -> Self {
+        args.to_string()
+    }
+}
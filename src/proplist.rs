@@ -0,0 +1,212 @@
+use std::{collections::{btree_map, BTreeMap}, fmt, iter::FromIterator};
+
+/// Well-known property keys
+pub mod keys {
+    /// For streams: localized media name, formatted as UTF-8. E.g. "Guns'N'Roses: Civil War".
+    pub const MEDIA_NAME: &str = "media.name";
+    /// For streams: localized media title if applicable, formatted as UTF-8. E.g. "Civil War"
+    pub const MEDIA_TITLE: &str = "media.title";
+    /// For streams: localized media artist if applicable, formatted as UTF-8. E.g. "Guns'N'Roses"
+    pub const MEDIA_ARTIST: &str = "media.artist";
+    /// For streams: localized media copyright string if applicable, formatted as UTF-8
+    pub const MEDIA_COPYRIGHT: &str = "media.copyright";
+    /// For streams: localized media generator software string if applicable, formatted as UTF-8
+    pub const MEDIA_SOFTWARE: &str = "media.software";
+    /// For streams: media language if applicable, in standard POSIX format. E.g. "de_DE"
+    pub const MEDIA_LANGUAGE: &str = "media.language";
+    /// For streams: source filename if applicable, in URI format or local path
+    pub const MEDIA_FILENAME: &str = "media.filename";
+    /// For streams: icon for the media. A binary blob containing PNG image data
+    pub const MEDIA_ICON: &str = "media.icon";
+    /// For streams: an XDG icon name for the media. E.g. "audio-x-mp3"
+    pub const MEDIA_ICON_NAME: &str = "media.icon_name";
+    /// For streams: logic role of this media. One of "video", "music", "game", "event", "phone",
+    /// "animation", "production", "a11y", "test"
+    pub const MEDIA_ROLE: &str = "media.role";
+
+    /// For clients/streams: localized human readable application name. E.g. "Totem Music Player"
+    pub const APPLICATION_NAME: &str = "application.name";
+    /// For clients/streams: a textual id for identifying an application logically. E.g. "org.gnome.Totem"
+    pub const APPLICATION_ID: &str = "application.id";
+    /// For clients/streams: a version string, e.g. "0.6.88"
+    pub const APPLICATION_VERSION: &str = "application.version";
+    /// For clients/streams: application icon. A binary blob containing PNG image data
+    pub const APPLICATION_ICON: &str = "application.icon";
+    /// For clients/streams: an XDG icon name for the application. E.g. "totem"
+    pub const APPLICATION_ICON_NAME: &str = "application.icon_name";
+    /// For clients/streams: application language if applicable, in standard POSIX format. E.g. "de_DE"
+    pub const APPLICATION_LANGUAGE: &str = "application.language";
+    /// For clients/streams on UNIX: application process PID, an integer formatted as string. E.g. "4711"
+    pub const APPLICATION_PROCESS_ID: &str = "application.process.id";
+    /// For clients/streams: application process name. E.g. "totem"
+    pub const APPLICATION_PROCESS_BINARY: &str = "application.process.binary";
+    /// For clients/streams: application user name. E.g. "jonas"
+    pub const APPLICATION_PROCESS_USER: &str = "application.process.user";
+    /// For clients/streams: host name the application runs on. E.g. "omega"
+    pub const APPLICATION_PROCESS_HOST: &str = "application.process.host";
+    /// For clients/streams: the D-Bus host id the application runs on
+    pub const APPLICATION_PROCESS_MACHINE_ID: &str = "application.process.machine_id";
+    /// For clients/streams: an id for the login session the application runs in
+    pub const APPLICATION_PROCESS_SESSION_ID: &str = "application.process.session_id";
+
+    /// For devices: device string in the underlying audio layer's format. E.g. "surround51:0"
+    pub const DEVICE_STRING: &str = "device.string";
+    /// For devices: API this device is access with. E.g. "alsa"
+    pub const DEVICE_API: &str = "device.api";
+    /// For devices: localized human readable device one-line description. E.g. "Foobar Industries USB Headset 2000+ Ultra"
+    pub const DEVICE_DESCRIPTION: &str = "device.description";
+    /// For devices: bus path to the device in the OS' format. E.g. "/sys/bus/pci/devices/0000:00:1f.2"
+    pub const DEVICE_BUS_PATH: &str = "device.bus_path";
+    /// For devices: serial number if applicable. E.g. "4711-0815-1234"
+    pub const DEVICE_SERIAL: &str = "device.serial";
+    /// For devices: vendor ID if applicable. E.g. 1274
+    pub const DEVICE_VENDOR_ID: &str = "device.vendor.id";
+    /// For devices: vendor name if applicable. E.g. "Foocorp Heavy Industries"
+    pub const DEVICE_VENDOR_NAME: &str = "device.vendor.name";
+    /// For devices: product ID if applicable. E.g. 4565
+    pub const DEVICE_PRODUCT_ID: &str = "device.product.id";
+    /// For devices: product name if applicable. E.g. "SuperSpeakers 2000 Pro"
+    pub const DEVICE_PRODUCT_NAME: &str = "device.product.name";
+    /// For devices: device class. One of "sound", "modem", "monitor", "filter"
+    pub const DEVICE_CLASS: &str = "device.class";
+    /// For devices: form factor if applicable. One of "internal", "speaker", "handset", "tv",
+    /// "webcam", "microphone", "headset", "headphone", "hands-free", "car", "hifi", "computer", "portable"
+    pub const DEVICE_FORM_FACTOR: &str = "device.form_factor";
+    /// For devices: bus of the device if applicable. One of "isa", "pci", "usb", "firewire", "bluetooth"
+    pub const DEVICE_BUS: &str = "device.bus";
+    /// For devices: icon for the device. A binary blob containing PNG image data
+    pub const DEVICE_ICON: &str = "device.icon";
+    /// For devices: an XDG icon name for the device. E.g. "sound-card-speakers-usb"
+    pub const DEVICE_ICON_NAME: &str = "device.icon_name";
+    /// For devices: access mode of the device if applicable. One of "mmap", "mmap_rewrite", "serial"
+    pub const DEVICE_ACCESS_MODE: &str = "device.access_mode";
+    /// For filter devices: master device id if applicable
+    pub const DEVICE_MASTER_DEVICE: &str = "device.master_device";
+    /// For devices: buffer size in bytes, integer formatted as string
+    pub const DEVICE_BUFFERING_BUFFER_SIZE: &str = "device.buffering.buffer_size";
+    /// For devices: fragment size in bytes, integer formatted as string
+    pub const DEVICE_BUFFERING_FRAGMENT_SIZE: &str = "device.buffering.fragment_size";
+    /// For devices: profile identifier for the profile this devices is in. E.g. "analog-stereo"
+    pub const DEVICE_PROFILE_NAME: &str = "device.profile.name";
+    /// For devices: human readable one-line description of the profile this device is in. E.g. "Analog Stereo"
+    pub const DEVICE_PROFILE_DESCRIPTION: &str = "device.profile.description";
+    /// For devices: intended use. A space separated list of roles (see `MEDIA_ROLE`) this device is particularly well suited for
+    pub const DEVICE_INTENDED_ROLES: &str = "device.intended_roles";
+
+    /// For formats: the sample format used by PCM formats, as JSON string. E.g. "\"s16le\""
+    pub const FORMAT_SAMPLE_FORMAT: &str = "format.sample_format";
+    /// For formats: the sample rate, as JSON integer or list/range
+    pub const FORMAT_RATE: &str = "format.rate";
+    /// For formats: the number of channels, as JSON integer or list/range
+    pub const FORMAT_CHANNELS: &str = "format.channels";
+    /// For PCM formats: the channel map of the stream as returned by `pa_channel_map_snprint()`
+    pub const FORMAT_CHANNEL_MAP: &str = "format.channel_map";
+}
+
+/// A property list, mapping string keys to arbitrary byte values.
+///
+/// Text values are stored NUL terminated, like libpulse does.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Proplist {
+    entries: BTreeMap<String, Vec<u8>>,
+}
+
+impl Proplist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<Vec<u8>>) {
+        self.entries.insert(key.into(), value.into());
+    }
+
+    pub fn set_str(&mut self, key: impl Into<String>, value: &str) {
+        let mut bytes = Vec::with_capacity(value.len() + 1);
+        bytes.extend_from_slice(value.as_bytes());
+        bytes.push(b'\0');
+
+        self.set(key, bytes);
+    }
+
+    pub fn get(&self, key: &str) -> Option<&[u8]> {
+        self.entries.get(key).map(Vec::as_slice)
+    }
+
+    /// Returns the value as text if it is valid NUL terminated UTF-8
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        let value = self.get(key)?;
+        let value = value.strip_suffix(b"\0")?;
+
+        std::str::from_utf8(value).ok()
+    }
+
+    /// Returns all text values. Non-text values are skipped.
+    pub fn iter_str(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.keys().filter_map(move |key| Some((key.as_str(), self.get_str(key)?)))
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Vec<u8>> {
+        self.entries.remove(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> btree_map::Iter<'_, String, Vec<u8>> {
+        self.entries.iter()
+    }
+}
+
+impl IntoIterator for Proplist {
+    type Item = (String, Vec<u8>);
+    type IntoIter = btree_map::IntoIter<String, Vec<u8>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+/// Collects text properties
+impl<K: Into<String>, V: AsRef<str>> FromIterator<(K, V)> for Proplist {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut proplist = Self::new();
+
+        for (key, value) in iter {
+            proplist.set_str(key, value.as_ref());
+        }
+
+        proplist
+    }
+}
+
+/// Formats the proplist like `pa_proplist_to_string`, one `key = "value"` per line.
+/// Non-text values are shown as hex.
+impl fmt::Display for Proplist {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (key, value) in &self.entries {
+            match self.get_str(key) {
+                Some(text) => writeln!(f, "{} = \"{}\"", key, text.replace('"', "\\\""))?,
+                None => {
+                    write!(f, "{} = hex:", key)?;
+
+                    for byte in value {
+                        write!(f, "{:02x}", byte)?;
+                    }
+
+                    writeln!(f)?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
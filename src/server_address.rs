@@ -0,0 +1,218 @@
+use anyhow::*;
+use crate::config::ClientConfig;
+use std::{env, fmt, fs, net::SocketAddr, path::PathBuf, str::FromStr};
+
+/// Socket path of a system wide server
+pub const SYSTEM_SOCKET_PATH: &str = "/var/run/pulse/native";
+/// Port used for TCP connections if none is given
+pub const DEFAULT_PORT: u16 = 4713;
+/// Files the machine id is read from, in order of preference
+const MACHINE_ID_PATHS: &[&str] = &["/etc/machine-id", "/var/lib/dbus/machine-id"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerAddress {
+    /// Path to a unix domain socket
+    Unix(PathBuf),
+    /// Host name or IP address and port of a TCP socket
+    Tcp {
+        host: String,
+        port: u16,
+        family: AddressFamily,
+    },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AddressFamily {
+    /// Use whatever the host name resolves to
+    Any,
+    /// Only use IPv4 addresses
+    V4,
+    /// Only use IPv6 addresses
+    V6,
+}
+
+impl AddressFamily {
+    pub fn matches(&self, address: &SocketAddr) -> bool {
+        match self {
+            Self::Any => true,
+            Self::V4 => address.is_ipv4(),
+            Self::V6 => address.is_ipv6(),
+        }
+    }
+}
+
+impl ServerAddress {
+    pub fn unix(path: impl Into<PathBuf>) -> Self {
+        Self::Unix(path.into())
+    }
+
+    pub fn tcp(host: impl Into<String>, port: u16) -> Self {
+        Self::Tcp {
+            host: host.into(),
+            port,
+            family: AddressFamily::Any,
+        }
+    }
+
+    /// Returns the addresses to try when connecting, in order of preference.
+    ///
+    /// If a default server is configured (e.g. via `PULSE_SERVER`) only the servers listed in it are returned.
+    /// Otherwise the per-user runtime directory (`PULSE_RUNTIME_PATH` or
+    /// `$XDG_RUNTIME_DIR/pulse`) is tried first, followed by the system wide socket
+    /// and, if `auto-connect-localhost` is enabled, TCP on localhost.
+    /// Configured servers that fail to parse are included as errors.
+    pub fn candidates(config: &ClientConfig) -> Vec<Result<Self>> {
+        if let Some(servers) = &config.default_server {
+            return parse_server_list(servers);
+        }
+
+        let mut candidates = Vec::new();
+
+        if let Some(runtime_path) = env::var_os("PULSE_RUNTIME_PATH") {
+            candidates.push(Self::unix(PathBuf::from(runtime_path).join("native")));
+        }
+
+        if let Some(runtime_dir) = env::var_os("XDG_RUNTIME_DIR") {
+            candidates.push(Self::unix(PathBuf::from(runtime_dir).join("pulse/native")));
+        }
+
+        candidates.push(Self::unix(SYSTEM_SOCKET_PATH));
+
+        if config.auto_connect_localhost {
+            candidates.push(Self::Tcp {
+                host: "127.0.0.1".into(),
+                port: DEFAULT_PORT,
+                family: AddressFamily::V4,
+            });
+            candidates.push(Self::Tcp {
+                host: "::1".into(),
+                port: DEFAULT_PORT,
+                family: AddressFamily::V6,
+            });
+        }
+
+        candidates.dedup();
+
+        candidates.into_iter().map(Ok).collect()
+    }
+}
+
+/// Parses a whitespace separated list of server strings as found in `PULSE_SERVER`.
+/// Entries that fail to parse, or are meant for another machine, are returned as errors.
+pub fn parse_server_list(servers: &str) -> Vec<Result<ServerAddress>> {
+    servers
+        .split_whitespace()
+        .map(|server| server.parse()
+            .with_context(|| format!("Invalid server address {:?}", server)))
+        .collect()
+}
+
+/// Reads the id of this machine as used in `{machine-id}` qualified server addresses
+fn machine_id() -> Option<String> {
+    MACHINE_ID_PATHS.iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_owned())
+        .find(|id| !id.is_empty())
+}
+
+impl FromStr for ServerAddress {
+    type Err = Error;
+
+    fn from_str(address: &str) -> Result<Self> {
+        // Addresses qualified with `{machine-id}` are only valid on that machine
+        let address = match address.strip_prefix('{') {
+            Some(rest) => {
+                let (id, address) = rest.split_once('}')
+                    .context("Unterminated machine id in server address")?;
+                let machine_id = machine_id()
+                    .context("Unable to determine the machine id")?;
+                ensure!(id == machine_id, "Server address is for machine {}", id);
+
+                address
+            },
+            None => address,
+        };
+
+        if let Some(path) = address.strip_prefix("unix:") {
+            ensure!(path.starts_with('/'), "Unix socket path must be absolute");
+            return Ok(Self::unix(path));
+        }
+
+        if address.starts_with('/') {
+            return Ok(Self::unix(address));
+        }
+
+        let (address, family) = if let Some(address) = address.strip_prefix("tcp4:") {
+            (address, AddressFamily::V4)
+        } else if let Some(address) = address.strip_prefix("tcp6:") {
+            (address, AddressFamily::V6)
+        } else {
+            (address.strip_prefix("tcp:").unwrap_or(address), AddressFamily::Any)
+        };
+
+        let (host, port) = parse_host_and_port(address)?;
+
+        Ok(Self::Tcp {
+            host: host.to_owned(),
+            port,
+            family,
+        })
+    }
+}
+
+/// Splits `host`, `host:port`, `[ipv6]` or `[ipv6]:port` into its parts.
+/// Bare IPv6 addresses without brackets are taken as host without port.
+fn parse_host_and_port(address: &str) -> Result<(&str, u16)> {
+    let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+        let end = rest.find(']')
+            .context("Unterminated IPv6 address")?;
+        let port = &rest[end + 1..];
+        let port = match port.strip_prefix(':') {
+            Some(port) => Some(port),
+            None if port.is_empty() => None,
+            None => bail!("Unexpected trailing characters after IPv6 address"),
+        };
+
+        (&rest[..end], port)
+    } else {
+        match address.matches(':').count() {
+            0 => (address, None),
+            1 => {
+                let mut parts = address.splitn(2, ':');
+                (parts.next().unwrap(), parts.next())
+            },
+            _ => (address, None),
+        }
+    };
+
+    ensure!(!host.is_empty(), "Missing host name");
+
+    let port = match port {
+        Some(port) => port.parse()
+            .with_context(|| format!("Invalid port {:?}", port))?,
+        None => DEFAULT_PORT,
+    };
+
+    Ok((host, port))
+}
+
+impl fmt::Display for ServerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+            Self::Tcp { host, port, family } => {
+                let prefix = match family {
+                    AddressFamily::Any => "tcp",
+                    AddressFamily::V4 => "tcp4",
+                    AddressFamily::V6 => "tcp6",
+                };
+
+                if host.contains(':') {
+                    write!(f, "{}:[{}]:{}", prefix, host, port)
+                } else {
+                    write!(f, "{}:{}:{}", prefix, host, port)
+                }
+            },
+        }
+    }
+}
@@ -1,21 +1,37 @@
 use anyhow::*;
-use crate::{frame::Frame, Client};
+use crate::{frame::Frame, Client, format::FormatInfo, command::StreamEvent};
 use bytes::BytesMut;
+use futures::Stream;
 
 #[derive(Clone)]
 pub struct PlaybackStream {
     channel: u32,
     client: Client,
+    format: Option<FormatInfo>,
 }
 
 impl PlaybackStream {
-    pub(crate) fn new(client: &Client, channel: u32) -> Self {
+    pub(crate) fn new(client: &Client, channel: u32, format: Option<FormatInfo>) -> Self {
         Self {
             channel,
             client: client.clone(),
+            format,
         }
     }
 
+    /// The format negotiated with the server. Only known since protocol v21.
+    pub fn format(&self) -> Option<&FormatInfo> {
+        self.format.as_ref()
+    }
+
+    /// Returns a stream of events the server sends for this stream, e.g. data requests and underflows.
+    ///
+    /// Events sent while nobody listens are delivered to `Client::events` instead.
+    /// The stream ends when the server kills the stream.
+    pub async fn events(&self) -> impl Stream<Item = StreamEvent> {
+        self.client.playback_stream_events(self.channel).await
+    }
+
     /// This is currently slightly more efficient than `write_slice`.
     pub async fn write_bytes(&self, data: BytesMut) -> Result<()> {
         let frame = Frame {
@@ -1,14 +1,22 @@
 use std::collections::VecDeque;
 use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::io::{Read, Cursor, BufRead};
 use anyhow::*;
 use byteorder::{ReadBytesExt, BE};
-use crate::{channel::{ChannelPosition, CHANNELS_MAX}, sample::SampleFormat};
+use crate::{channel::{ChannelPosition, CHANNELS_MAX}, sample::SampleFormat, proplist::Proplist, format::{FormatInfo, Encoding}, PROTOCOL_VERSION, VOLUME_MAX};
 use bytes::{BufMut, BytesMut};
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct TagStruct {
     values: VecDeque<Value>,
+    protocol_version: u32,
+}
+
+impl Default for TagStruct {
+    fn default() -> Self {
+        Self::with_protocol_version(PROTOCOL_VERSION)
+    }
 }
 
 impl TagStruct {
@@ -16,6 +24,23 @@ impl TagStruct {
         Self::default()
     }
 
+    /// Creates an empty tag struct for the given negotiated protocol version.
+    /// `Put` and `Pop` implementations use it to decide which fields to encode.
+    pub fn with_protocol_version(protocol_version: u32) -> Self {
+        Self {
+            values: VecDeque::new(),
+            protocol_version,
+        }
+    }
+
+    pub fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    pub fn set_protocol_version(&mut self, protocol_version: u32) {
+        self.protocol_version = protocol_version;
+    }
+
     pub fn parse(bytes: &[u8]) -> Result<Self> {
         let len = bytes.len() as u64;
         let mut bytes = Cursor::new(bytes);
@@ -27,7 +52,10 @@ impl TagStruct {
             values.push_back(value);
         }
 
-        Ok(Self { values })
+        Ok(Self {
+            values,
+            protocol_version: PROTOCOL_VERSION,
+        })
     }
 
     pub fn to_bytes(&self) -> Result<BytesMut> {
@@ -84,6 +112,42 @@ impl TagStruct {
         self.put_value(Value::U32(value));
     }
 
+    pub fn pop_u64(&mut self) -> Result<u64> {
+        self.pop_value()?.into_u64()
+    }
+
+    pub fn put_u64(&mut self, value: u64) {
+        self.put_value(Value::U64(value));
+    }
+
+    pub fn pop_s64(&mut self) -> Result<i64> {
+        self.pop_value()?.into_s64()
+    }
+
+    pub fn put_s64(&mut self, value: i64) {
+        self.put_value(Value::S64(value));
+    }
+
+    /// Pops a duration in microseconds.
+    /// Note that "invalid" usec values (`u64::MAX`) are returned as a very long duration.
+    pub fn pop_usec(&mut self) -> Result<Duration> {
+        self.pop_value()?.into_usec().map(Duration::from_micros)
+    }
+
+    /// Puts a duration in microseconds, saturating at `u64::MAX`
+    pub fn put_usec(&mut self, value: Duration) {
+        let usec = u64::try_from(value.as_micros()).unwrap_or(u64::MAX);
+        self.put_value(Value::Usec(usec));
+    }
+
+    pub fn pop_timeval(&mut self) -> Result<SystemTime> {
+        self.pop_value()?.into_timeval()
+    }
+
+    pub fn put_timeval(&mut self, value: SystemTime) {
+        self.put_value(Value::Timeval(value));
+    }
+
     pub fn pop_string(&mut self) -> Result<Option<String>> {
         self.pop_value()?.into_string()
     }
@@ -104,6 +168,10 @@ impl TagStruct {
         self.put_value(Value::ChannelMap(value));
     }
 
+    pub fn pop_channel_map(&mut self) -> Result<ChannelMap> {
+        self.pop_value()?.into_channel_map()
+    }
+
     pub fn put_sample_spec(&mut self, value: SampleSpec) {
         self.put_value(Value::SampleSpec(value));
     }
@@ -115,6 +183,34 @@ impl TagStruct {
     pub fn put_channel_volume(&mut self, value: ChannelVolume) {
         self.put_value(Value::ChannelVolume(value));
     }
+
+    pub fn pop_channel_volume(&mut self) -> Result<ChannelVolume> {
+        self.pop_value()?.into_channel_volume()
+    }
+
+    pub fn put_volume(&mut self, value: u32) {
+        self.put_value(Value::Volume(value));
+    }
+
+    pub fn pop_volume(&mut self) -> Result<u32> {
+        self.pop_value()?.into_volume()
+    }
+
+    pub fn put_proplist(&mut self, value: Proplist) {
+        self.put_value(Value::Proplist(value));
+    }
+
+    pub fn pop_proplist(&mut self) -> Result<Proplist> {
+        self.pop_value()?.into_proplist()
+    }
+
+    pub fn put_format_info(&mut self, value: FormatInfo) {
+        self.put_value(Value::FormatInfo(value));
+    }
+
+    pub fn pop_format_info(&mut self) -> Result<FormatInfo> {
+        self.pop_value()?.into_format_info()
+    }
 }
 
 #[derive(Debug)]
@@ -122,11 +218,19 @@ pub enum Value {
     Bool(bool),
     U8(u8),
     U32(u32),
+    U64(u64),
+    S64(i64),
+    /// Microseconds
+    Usec(u64),
+    Timeval(SystemTime),
     String(Option<String>),
     Arbitrary(Vec<u8>),
     SampleSpec(SampleSpec),
     ChannelMap(ChannelMap),
     ChannelVolume(ChannelVolume),
+    Volume(u32),
+    Proplist(Proplist),
+    FormatInfo(FormatInfo),
 }
 
 impl Value {
@@ -138,31 +242,19 @@ impl Value {
             tag::BOOLEAN_FALSE => Value::Bool(false),
             tag::U8 => Value::U8(reader.read_u8()?),
             tag::U32 => Value::U32(reader.read_u32::<BE>()?),
-            tag::STRING_NULL => Value::String(None),
-            tag::STRING => {
-                let mut value = Vec::new();
-
-                reader.read_until(b'\0', &mut value)?;
-
-                let mut value = String::from_utf8(value)?;
-
-                if value.as_bytes().last() != Some(&b'\0') {
-                    bail!("String did not end with NULL");
-                }
-
-                value.pop();
-
-                Value::String(Some(value))
-            }
-            tag::ARBITRARY => {
-                let len = reader.read_u32::<BE>()?;
-                let len = usize::try_from(len)
-                    .context("Arbitrary value len exceeds pointer width")?;
-                let mut value = vec![0; len];
-                reader.read_exact(&mut value)?;
-
-                Value::Arbitrary(value)
+            tag::U64 => Value::U64(reader.read_u64::<BE>()?),
+            tag::S64 => Value::S64(reader.read_i64::<BE>()?),
+            tag::USEC => Value::Usec(reader.read_u64::<BE>()?),
+            tag::TIMEVAL => {
+                let secs = reader.read_u32::<BE>()?;
+                let usecs = reader.read_u32::<BE>()?;
+                let since_epoch = Duration::from_secs(secs.into()) + Duration::from_micros(usecs.into());
+
+                Value::Timeval(UNIX_EPOCH + since_epoch)
             },
+            tag::STRING_NULL => Value::String(None),
+            tag::STRING => Value::String(Some(read_string(reader)?)),
+            tag::ARBITRARY => Value::Arbitrary(read_arbitrary(reader)?),
             tag::SAMPLE_SPEC => {
                 let value = SampleSpec {
                     format: {
@@ -175,6 +267,56 @@ impl Value {
 
                 Value::SampleSpec(value)
             }
+            tag::CHANNEL_MAP => {
+                let num_channels = usize::from(reader.read_u8()?);
+
+                ensure!(num_channels <= CHANNELS_MAX, "Channel map has too many channels ({})", num_channels);
+
+                let mut positions = Vec::with_capacity(num_channels);
+
+                for _ in 0..num_channels {
+                    let position = reader.read_u8()?;
+                    let position = ChannelPosition::try_from(position)
+                        .map_err(|_| anyhow!("Unknown channel position {}", position))?;
+
+                    ensure!(!matches!(position, ChannelPosition::Invalid), "Invalid channel position in channel map");
+
+                    positions.push(position);
+                }
+
+                Value::ChannelMap(ChannelMap { positions })
+            },
+            tag::CVOLUME => {
+                let num_channels = usize::from(reader.read_u8()?);
+
+                ensure!(num_channels <= CHANNELS_MAX, "Channel volume has too many channels ({})", num_channels);
+
+                let mut volumes = Vec::with_capacity(num_channels);
+
+                for _ in 0..num_channels {
+                    let volume = reader.read_u32::<BE>()?;
+
+                    ensure!(volume <= VOLUME_MAX, "Invalid volume {}", volume);
+
+                    volumes.push(volume);
+                }
+
+                Value::ChannelVolume(ChannelVolume { volumes })
+            },
+            tag::VOLUME => Value::Volume(reader.read_u32::<BE>()?),
+            tag::PROPLIST => Value::Proplist(read_proplist(reader)?),
+            tag::FORMAT_INFO => {
+                // Read in place, like proplists, to avoid unbounded recursion
+                expect_tag(reader, tag::U8).context("Missing format info encoding")?;
+                let encoding = reader.read_u8()?;
+                let encoding = Encoding::try_from(encoding)
+                    .map_err(|_| anyhow!("Unknown format encoding {}", encoding))?;
+
+                expect_tag(reader, tag::PROPLIST).context("Missing format info proplist")?;
+                let proplist = read_proplist(reader)?;
+
+                Value::FormatInfo(FormatInfo { encoding, proplist })
+            }
             _ => bail!("Unimplemented tag '{}'", tag as char),
         })
     }
@@ -193,6 +335,28 @@ impl Value {
                 bytes.put_u8(tag::U32);
                 bytes.put_u32(*value);
             },
+            Self::U64(value) => {
+                bytes.put_u8(tag::U64);
+                bytes.put_u64(*value);
+            },
+            Self::S64(value) => {
+                bytes.put_u8(tag::S64);
+                bytes.put_i64(*value);
+            },
+            Self::Usec(value) => {
+                bytes.put_u8(tag::USEC);
+                bytes.put_u64(*value);
+            },
+            Self::Timeval(value) => {
+                let since_epoch = value.duration_since(UNIX_EPOCH)
+                    .context("Timeval is before the unix epoch")?;
+                let secs = u32::try_from(since_epoch.as_secs())
+                    .context("Timeval seconds exceed 32 bits")?;
+
+                bytes.put_u8(tag::TIMEVAL);
+                bytes.put_u32(secs);
+                bytes.put_u32(since_epoch.subsec_micros());
+            },
             Self::Arbitrary(value) => {
                 bytes.put_u8(tag::ARBITRARY);
                 let len = u32::try_from(value.len())
@@ -240,6 +404,17 @@ impl Value {
                     bytes.put_u32(volume);
                 }
             },
+            Value::Volume(volume) => {
+                bytes.put_u8(tag::VOLUME);
+                bytes.put_u32(*volume);
+            },
+            Value::Proplist(proplist) => write_proplist(proplist, bytes)?,
+            Value::FormatInfo(format) => {
+                bytes.put_u8(tag::FORMAT_INFO);
+                bytes.put_u8(tag::U8);
+                bytes.put_u8(format.encoding.into());
+                write_proplist(&format.proplist, bytes)?;
+            },
         }
 
         Ok(())
@@ -266,6 +441,34 @@ impl Value {
         }
     }
 
+    fn into_u64(self) -> Result<u64> {
+        match self {
+            Self::U64(value) => Ok(value),
+            _ => bail!("Expected u64 value"),
+        }
+    }
+
+    fn into_s64(self) -> Result<i64> {
+        match self {
+            Self::S64(value) => Ok(value),
+            _ => bail!("Expected s64 value"),
+        }
+    }
+
+    fn into_usec(self) -> Result<u64> {
+        match self {
+            Self::Usec(value) => Ok(value),
+            _ => bail!("Expected usec value"),
+        }
+    }
+
+    fn into_timeval(self) -> Result<SystemTime> {
+        match self {
+            Self::Timeval(value) => Ok(value),
+            _ => bail!("Expected timeval value"),
+        }
+    }
+
     fn into_string(self) -> Result<Option<String>> {
         match self {
             Self::String(value) => Ok(value),
@@ -286,6 +489,41 @@ impl Value {
             _ => bail!("Expected sample spec value")
         }
     }
+
+    fn into_format_info(self) -> Result<FormatInfo> {
+        match self {
+            Self::FormatInfo(value) => Ok(value),
+            _ => bail!("Expected format info value")
+        }
+    }
+
+    fn into_channel_map(self) -> Result<ChannelMap> {
+        match self {
+            Self::ChannelMap(value) => Ok(value),
+            _ => bail!("Expected channel map value")
+        }
+    }
+
+    fn into_channel_volume(self) -> Result<ChannelVolume> {
+        match self {
+            Self::ChannelVolume(value) => Ok(value),
+            _ => bail!("Expected channel volume value")
+        }
+    }
+
+    fn into_volume(self) -> Result<u32> {
+        match self {
+            Self::Volume(value) => Ok(value),
+            _ => bail!("Expected volume value")
+        }
+    }
+
+    fn into_proplist(self) -> Result<Proplist> {
+        match self {
+            Self::Proplist(value) => Ok(value),
+            _ => bail!("Expected proplist value")
+        }
+    }
 }
 
 
@@ -331,12 +569,21 @@ impl ChannelMap {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ChannelVolume {
     /// Volume per channel
     pub volumes: Vec<u32>, // Use SmallVec?
 }
 
+impl ChannelVolume {
+    /// The same volume on all `channels`
+    pub fn uniform(channels: usize, volume: u32) -> Self {
+        Self {
+            volumes: vec![volume; channels],
+        }
+    }
+}
+
 mod tag {
     pub const INVALID: u8 = 0;
     pub const STRING: u8 = b't';
@@ -367,6 +614,111 @@ pub trait Put {
     fn put(self, tag_struct: &mut TagStruct);
 }
 
+/// Pops values until the tag struct is empty, as used by list replies
+impl<T: Pop> Pop for Vec<T> {
+    fn pop(tag_struct: &mut TagStruct) -> Result<Self> {
+        let mut values = Vec::new();
+
+        while !tag_struct.is_empty() {
+            values.push(tag_struct.pop::<T>()?);
+        }
+
+        Ok(values)
+    }
+}
+
+/// Empty reply, as sent by the server to acknowledge a command
+impl Pop for () {
+    fn pop(_tag_struct: &mut TagStruct) -> Result<Self> {
+        Ok(())
+    }
+}
+
+/// Reads the contents of a `STRING` value, after its tag
+fn read_string<R: Read + BufRead>(reader: &mut R) -> Result<String> {
+    let mut value = Vec::new();
+
+    reader.read_until(b'\0', &mut value)?;
+
+    let mut value = String::from_utf8(value)?;
+
+    if value.as_bytes().last() != Some(&b'\0') {
+        bail!("String did not end with NULL");
+    }
+
+    value.pop();
+
+    Ok(value)
+}
+
+/// Reads the contents of an `ARBITRARY` value, after its tag
+fn read_arbitrary<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let len = reader.read_u32::<BE>()?;
+    let len = usize::try_from(len)
+        .context("Arbitrary value len exceeds pointer width")?;
+    let mut value = vec![0; len];
+    reader.read_exact(&mut value)?;
+
+    Ok(value)
+}
+
+/// Reads the contents of a `PROPLIST` value, after its tag.
+/// The nested values are read in place rather than through `Value::read_from`,
+/// so malicious input can't recurse without bound.
+fn read_proplist<R: Read + BufRead>(reader: &mut R) -> Result<Proplist> {
+    let mut proplist = Proplist::new();
+
+    loop {
+        let key = match reader.read_u8()? {
+            tag::STRING => read_string(reader)?,
+            tag::STRING_NULL => break,
+            _ => bail!("Expected proplist key"),
+        };
+
+        expect_tag(reader, tag::U32).context("Missing proplist value length")?;
+        let len = reader.read_u32::<BE>()?;
+
+        expect_tag(reader, tag::ARBITRARY).context("Missing proplist value")?;
+        let value = read_arbitrary(reader)?;
+
+        ensure!(value.len() as u64 == u64::from(len), "Proplist value length mismatch");
+
+        proplist.set(key, value);
+    }
+
+    Ok(proplist)
+}
+
+fn expect_tag<R: Read>(reader: &mut R, expected: u8) -> Result<()> {
+    let tag = reader.read_u8()?;
+
+    ensure!(tag == expected, "Expected tag '{}', got '{}'", expected as char, tag as char);
+
+    Ok(())
+}
+
+fn write_proplist(proplist: &Proplist, bytes: &mut BytesMut) -> Result<()> {
+    bytes.put_u8(tag::PROPLIST);
+
+    for (key, value) in proplist.iter() {
+        bytes.put_u8(tag::STRING);
+        bytes.put_slice(truncate_to_before_first_null(key).as_bytes());
+        bytes.put_u8(b'\0');
+
+        let len = u32::try_from(value.len())
+            .context("Property value len exceeds 32 bits")?;
+        bytes.put_u8(tag::U32);
+        bytes.put_u32(len);
+        bytes.put_u8(tag::ARBITRARY);
+        bytes.put_u32(len);
+        bytes.put_slice(value);
+    }
+
+    bytes.put_u8(tag::STRING_NULL);
+
+    Ok(())
+}
+
 fn truncate_to_before_first_null(s: &str) -> &str {
     s.split('\0').next().unwrap()
 }
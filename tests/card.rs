@@ -0,0 +1,82 @@
+use repulse::{command::{CardInfo, PortAvailable, Direction}, tag_struct::TagStruct};
+
+/// `GetCardInfo` reply of a Bluetooth headset as sent by a v35 server
+const BLUETOOTH_CARD_REPLY: &[&[u8]] = &[
+    b"L\x00\x00\x00\x03", // index
+    b"tbluez_card.00_1B_66_AA_BB_CC\0", // name
+    b"L\x00\x00\x00\x19", // owner module
+    b"tmodule-bluez5-device.c\0", // driver
+    b"L\x00\x00\x00\x02", // profile count
+    b"ta2dp_sink\0", // profile name
+    b"tHigh Fidelity Playback (A2DP Sink)\0", // profile description
+    b"L\x00\x00\x00\x01", // sinks
+    b"L\x00\x00\x00\x00", // sources
+    b"L\x00\x00\x00\x28", // priority
+    b"L\x00\x00\x00\x01", // available
+    b"theadset_head_unit\0",
+    b"tHeadset Head Unit (HSP/HFP)\0",
+    b"L\x00\x00\x00\x01",
+    b"L\x00\x00\x00\x01",
+    b"L\x00\x00\x00\x1e",
+    b"L\x00\x00\x00\x00", // not available
+    b"ta2dp_sink\0", // active profile
+    b"P", // card proplist
+    b"tdevice.description\0",
+    b"L\x00\x00\x00\x08",
+    b"x\x00\x00\x00\x08Headset\0",
+    b"N",
+    b"L\x00\x00\x00\x01", // port count
+    b"theadset-output\0", // port name
+    b"tHeadset\0", // port description
+    b"L\x00\x00\x00\x00", // priority
+    b"L\x00\x00\x00\x02", // available: yes
+    b"B\x01", // direction: output
+    b"PN", // port proplist
+    b"L\x00\x00\x00\x02", // port profile count
+    b"ta2dp_sink\0",
+    b"theadset_head_unit\0",
+    b"r\x00\x00\x00\x00\x00\x00\x13\x88", // latency offset
+    b"N", // availability group
+    b"L\x00\x00\x00\x00", // port type
+];
+
+fn pop_card_info(version: u32) -> CardInfo {
+    let bytes = BLUETOOTH_CARD_REPLY.concat();
+    let mut tag_struct = TagStruct::parse(&bytes).unwrap();
+    tag_struct.set_protocol_version(version);
+
+    let card = tag_struct.pop::<CardInfo>().unwrap();
+    assert!(tag_struct.is_empty());
+
+    card
+}
+
+#[test]
+fn profile_availability_is_decoded_as_flag() {
+    let card = pop_card_info(35);
+
+    assert_eq!(card.profile("a2dp_sink").unwrap().available, Some(true));
+    assert_eq!(card.profile("headset_head_unit").unwrap().available, Some(false));
+}
+
+#[test]
+fn bluetooth_card_reply_is_decoded() {
+    let card = pop_card_info(35);
+
+    assert_eq!(card.index, 3);
+    assert_eq!(card.name, "bluez_card.00_1B_66_AA_BB_CC");
+    assert_eq!(card.owner_module, Some(25));
+    assert_eq!(card.active_profile.as_deref(), Some("a2dp_sink"));
+    assert_eq!(card.proplist.get_str("device.description"), Some("Headset"));
+
+    let profile = card.profile("headset_head_unit").unwrap();
+    assert_eq!((profile.n_sinks, profile.n_sources, profile.priority), (1, 1, 30));
+
+    let port = &card.ports[0];
+    assert_eq!(port.name, "headset-output");
+    assert_eq!(port.available, PortAvailable::Yes);
+    assert_eq!(port.direction, Direction::Output);
+    assert_eq!(port.profiles, ["a2dp_sink", "headset_head_unit"]);
+    assert_eq!(port.latency_offset, Some(5000));
+    assert_eq!(port.availability_group, None);
+}
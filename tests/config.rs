@@ -0,0 +1,73 @@
+use repulse::ClientConfig;
+use std::{env, fs, path::PathBuf, process};
+
+#[test]
+fn comments_and_sections_are_ignored() {
+    let mut config = ClientConfig::default();
+
+    config.parse("
+        # default-sink = hash
+        ; default-sink = semicolon
+        [General]
+        default-sink = speakers
+    ").unwrap();
+
+    assert_eq!(config.default_sink.as_deref(), Some("speakers"));
+}
+
+#[test]
+fn missing_equals_sign_is_rejected() {
+    let mut config = ClientConfig::default();
+
+    let err = config.parse("autospawn = no\nenable-shm\n").unwrap_err();
+
+    assert!(format!("{:#}", err).contains("line 2"), "{:#}", err);
+}
+
+#[test]
+fn boolean_spellings() {
+    let mut config = ClientConfig::default();
+
+    for value in &["1", "y", "t", "yes", "true", "on", "Yes", "TRUE"] {
+        config.autospawn = false;
+        config.set("autospawn", value).unwrap();
+        assert!(config.autospawn, "{:?} should be true", value);
+    }
+
+    for value in &["0", "n", "f", "no", "false", "off", "No", "OFF"] {
+        config.autospawn = true;
+        config.set("autospawn", value).unwrap();
+        assert!(!config.autospawn, "{:?} should be false", value);
+    }
+
+    assert!(config.set("autospawn", "maybe").is_err());
+}
+
+#[test]
+fn unknown_keys_are_ignored() {
+    let mut config = ClientConfig::default();
+
+    config.parse("no-such-setting = 1").unwrap();
+}
+
+#[tokio::test]
+async fn dot_d_files_are_applied_in_lexical_order() {
+    let dir = env::temp_dir().join(format!("repulse-config-test-{}", process::id()));
+    let path = dir.join("client.conf");
+    let dot_d = dir.join("client.conf.d");
+    fs::create_dir_all(&dot_d).unwrap();
+
+    fs::write(&path, "shm-size-bytes = 1\ncookie-file = /base\nenable-memfd = no\n").unwrap();
+    fs::write(dot_d.join("20-b.conf"), "shm-size-bytes = 20\n").unwrap();
+    fs::write(dot_d.join("10-a.conf"), "shm-size-bytes = 10\ncookie-file = /a\n").unwrap();
+    fs::write(dot_d.join("30-ignored.txt"), "shm-size-bytes = 30\n").unwrap();
+
+    env::set_var("PULSE_CLIENTCONFIG", &path);
+    let config = ClientConfig::load().await;
+    fs::remove_dir_all(&dir).unwrap();
+    let config = config.unwrap();
+
+    assert_eq!(config.shm_size_bytes, 20);
+    assert_eq!(config.cookie_file, Some(PathBuf::from("/a")));
+    assert!(!config.enable_memfd);
+}
@@ -0,0 +1,53 @@
+use repulse::{ClientConfig, Cookie, cookie::COOKIE_LENGTH};
+use std::{env, fs, process};
+
+#[test]
+fn cookie_from_hex() {
+    let cookie = Cookie::from_hex(&"ab".repeat(COOKIE_LENGTH)).unwrap();
+
+    assert_eq!(cookie.as_bytes(), &[0xab; COOKIE_LENGTH][..]);
+    assert!(Cookie::from_hex("abc").is_err());
+    assert!(Cookie::from_hex(&"ab".repeat(COOKIE_LENGTH - 1)).is_err());
+}
+
+#[tokio::test]
+async fn unusable_cookie_files_fall_back_to_zeroed_cookie() {
+    let dir = env::temp_dir().join(format!("repulse-cookie-test-{}", process::id()));
+    let short_cookie = dir.join("short-cookie");
+    let default_cookie = dir.join("pulse/cookie");
+    fs::create_dir_all(&default_cookie).unwrap();
+    fs::write(&short_cookie, [1; COOKIE_LENGTH - 1]).unwrap();
+
+    // Makes the default cookie path a directory, which can't be read
+    env::set_var("HOME", &dir);
+    env::set_var("XDG_CONFIG_HOME", &dir);
+    env::remove_var("PULSE_COOKIE");
+
+    let missing = Cookie::load(&ClientConfig {
+        cookie_file: Some(dir.join("missing-cookie")),
+        ..ClientConfig::default()
+    }).await;
+    let short = Cookie::load(&ClientConfig {
+        cookie_file: Some(short_cookie),
+        ..ClientConfig::default()
+    }).await;
+
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(missing, Cookie::zeroed());
+    assert_eq!(short, Cookie::zeroed());
+}
+
+#[tokio::test]
+async fn explicit_cookie_is_preferred_over_cookie_file() {
+    let cookie = Cookie::from_bytes(vec![2; COOKIE_LENGTH]).unwrap();
+    let config = ClientConfig {
+        cookie: Some(cookie.clone()),
+        cookie_file: Some("/nonexistent/cookie".into()),
+        ..ClientConfig::default()
+    };
+
+    env::remove_var("PULSE_COOKIE");
+
+    assert_eq!(Cookie::load(&config).await, cookie);
+}
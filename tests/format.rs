@@ -0,0 +1,99 @@
+use repulse::{
+    Proplist,
+    command::{CreatePlaybackStream, SinkRef},
+    format::{FormatInfo, Encoding},
+    tag_struct::{TagStruct, SampleSpec, ChannelMap, ChannelVolume},
+};
+
+fn encode(protocol_version: u32, put: impl FnOnce(&mut TagStruct)) -> Vec<u8> {
+    let mut tag_struct = TagStruct::new();
+    tag_struct.set_protocol_version(protocol_version);
+    put(&mut tag_struct);
+
+    tag_struct.to_bytes().unwrap().to_vec()
+}
+
+fn rate_48000() -> FormatInfo {
+    let mut format = FormatInfo::pcm();
+    format.set_rate(48000);
+    format
+}
+
+fn playback_stream(formats: Vec<FormatInfo>) -> CreatePlaybackStream {
+    CreatePlaybackStream {
+        name: "test".into(),
+        sample_spec: SampleSpec::pcm_signed_16bit_little_endian_stereo_44100hz(),
+        channel_map: ChannelMap::stereo(),
+        sink_ref: SinkRef::name("speakers"),
+        max_length: u32::MAX,
+        corked: false,
+        t_length: u32::MAX,
+        prebuf: u32::MAX,
+        min_req: u32::MAX,
+        sync_id: 0,
+        volume: ChannelVolume::uniform(2, repulse::VOLUME_NORMAL),
+        no_remap: false,
+        no_remix: false,
+        fix_format: false,
+        fix_rate: false,
+        fix_channels: false,
+        no_move: false,
+        variable_rate: false,
+        muted: false,
+        adjust_latency: false,
+        proplist: Proplist::new(),
+        volume_set: false,
+        early_requests: false,
+        muted_set: false,
+        dont_inhibit_auto_suspend: false,
+        fail_on_suspend: false,
+        relative_volume: false,
+        passthrough: false,
+        formats,
+    }
+}
+
+#[test]
+fn format_info_wire_format() {
+    let bytes = encode(21, |tag_struct| tag_struct.put_format_info(rate_48000()));
+
+    let mut expected = b"fB\x01P".to_vec();
+    expected.extend_from_slice(b"tformat.rate\0L\x00\x00\x00\x06x\x00\x00\x00\x06");
+    expected.extend_from_slice(b"48000\0");
+    expected.push(b'N');
+
+    assert_eq!(bytes, expected);
+
+    let format = TagStruct::parse(&bytes).unwrap().pop_format_info().unwrap();
+
+    assert_eq!(format.encoding, Encoding::PCM);
+    assert_eq!(format.proplist, rate_48000().proplist);
+}
+
+#[test]
+fn format_info_with_unknown_encoding_is_rejected() {
+    assert!(TagStruct::parse(b"fB\x64PN").is_err());
+}
+
+#[test]
+fn playback_stream_formats_are_only_sent_since_v21() {
+    let v20 = encode(20, |tag_struct| tag_struct.put(playback_stream(vec![rate_48000()])));
+    let v21 = encode(21, |tag_struct| tag_struct.put(playback_stream(vec![rate_48000()])));
+    let format = encode(21, |tag_struct| tag_struct.put_format_info(rate_48000()));
+
+    let mut expected = v20;
+    expected.extend_from_slice(b"B\x01");
+    expected.extend_from_slice(&format);
+
+    assert_eq!(v21, expected);
+}
+
+#[test]
+fn nested_format_info_is_rejected() {
+    assert!(TagStruct::parse(&vec![b'f'; 1_000_000]).is_err());
+}
+
+#[test]
+fn format_info_without_proplist_is_rejected() {
+    assert!(TagStruct::parse(b"fB\x01fB\x01PN").is_err());
+}
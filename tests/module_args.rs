@@ -0,0 +1,53 @@
+use anyhow::Result;
+use repulse::ModuleArguments;
+
+#[test]
+fn simple_values_are_not_quoted() -> Result<()> {
+    let args = ModuleArguments::new()
+        .arg("sink_name", "recording")?
+        .arg("rate", 48000)?
+        .arg("channels", 2)?;
+
+    assert_eq!(args.to_string(), "sink_name=recording rate=48000 channels=2");
+
+    Ok(())
+}
+
+#[test]
+fn values_with_whitespace_and_quotes_are_escaped() -> Result<()> {
+    let args = ModuleArguments::new()
+        .arg("sink_properties", "device.description=\"Recording Sink\"")?
+        .arg("path", "C:\\audio")?
+        .arg("empty", "")?;
+
+    assert_eq!(
+        args.to_string(),
+        r#"sink_properties="device.description=\"Recording Sink\"" path="C:\\audio" empty="""#,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn later_arguments_replace_earlier_ones() -> Result<()> {
+    let args = ModuleArguments::new()
+        .arg("sink_name", "a")?
+        .arg("rate", 44100)?
+        .arg("sink_name", "b")?;
+
+    assert_eq!(args.get("sink_name"), Some("b"));
+    assert_eq!(args.to_string(), "sink_name=b rate=44100");
+
+    Ok(())
+}
+
+#[test]
+fn invalid_keys_are_rejected() {
+    assert!(ModuleArguments::new().arg("a=b", "c").is_err());
+    assert!(ModuleArguments::new().arg("a b", "c").is_err());
+    assert!(ModuleArguments::new().arg("", "c").is_err());
+
+    let mut args = ModuleArguments::new();
+    assert!(args.set("a\"b", "c").is_err());
+    assert!(args.is_empty());
+}
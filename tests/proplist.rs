@@ -0,0 +1,88 @@
+use repulse::{Proplist, proplist::keys, tag_struct::TagStruct};
+
+fn round_trip(proplist: Proplist) -> Proplist {
+    let mut tag_struct = TagStruct::new();
+    tag_struct.put_proplist(proplist);
+
+    let bytes = tag_struct.to_bytes().unwrap();
+    let mut tag_struct = TagStruct::parse(&bytes).unwrap();
+    let proplist = tag_struct.pop_proplist().unwrap();
+
+    assert!(tag_struct.is_empty());
+
+    proplist
+}
+
+#[test]
+fn empty_proplist_round_trips() {
+    let proplist = Proplist::new();
+
+    let bytes = {
+        let mut tag_struct = TagStruct::new();
+        tag_struct.put_proplist(proplist.clone());
+        tag_struct.to_bytes().unwrap()
+    };
+
+    assert_eq!(&bytes[..], b"PN");
+    assert_eq!(round_trip(proplist), Proplist::new());
+}
+
+#[test]
+fn text_and_binary_values_round_trip() {
+    let mut proplist = Proplist::new();
+    proplist.set_str(keys::APPLICATION_NAME, "repulse");
+    proplist.set_str(keys::MEDIA_ROLE, "music");
+    proplist.set(keys::APPLICATION_ICON, vec![0x89, b'P', b'N', b'G', 0, 1, 2]);
+
+    let decoded = round_trip(proplist.clone());
+
+    assert_eq!(decoded, proplist);
+    assert_eq!(decoded.get_str(keys::APPLICATION_NAME), Some("repulse"));
+    assert_eq!(decoded.get_str(keys::APPLICATION_ICON), None);
+    assert_eq!(decoded.get(keys::APPLICATION_ICON), Some(&[0x89, b'P', b'N', b'G', 0, 1, 2][..]));
+}
+
+#[test]
+fn proplist_encoding_matches_wire_format() {
+    let proplist = vec![("a", "b")].into_iter().collect::<Proplist>();
+
+    let mut tag_struct = TagStruct::new();
+    tag_struct.put_proplist(proplist);
+    let bytes = tag_struct.to_bytes().unwrap();
+
+    assert_eq!(&bytes[..], &[
+        b'P',
+        b't', b'a', 0,
+        b'L', 0, 0, 0, 2,
+        b'x', 0, 0, 0, 2, b'b', 0,
+        b'N',
+    ][..]);
+}
+
+#[test]
+fn unterminated_proplist_is_rejected() {
+    assert!(TagStruct::parse(&[b'P', b't', b'a', 0]).is_err());
+}
+
+#[test]
+fn mismatched_value_length_is_rejected() {
+    let bytes = [
+        b'P',
+        b't', b'a', 0,
+        b'L', 0, 0, 0, 3,
+        b'x', 0, 0, 0, 2, b'b', 0,
+        b'N',
+    ];
+
+    assert!(TagStruct::parse(&bytes).is_err());
+}
+
+#[test]
+fn nested_proplist_is_rejected() {
+    assert!(TagStruct::parse(&vec![b'P'; 1_000_000]).is_err());
+}
+
+#[test]
+fn non_string_key_is_rejected() {
+    assert!(TagStruct::parse(&[b'P', b'L', 0, 0, 0, 1, b'N']).is_err());
+}
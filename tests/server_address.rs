@@ -0,0 +1,73 @@
+use repulse::{ServerAddress, server_address::{AddressFamily, DEFAULT_PORT, parse_server_list}};
+
+fn tcp(host: &str, port: u16, family: AddressFamily) -> ServerAddress {
+    ServerAddress::Tcp {
+        host: host.into(),
+        port,
+        family,
+    }
+}
+
+#[test]
+fn bracketed_ipv6_address_with_port() {
+    let address = "tcp:[::1]:4713".parse::<ServerAddress>().unwrap();
+
+    assert_eq!(address, tcp("::1", 4713, AddressFamily::Any));
+}
+
+#[test]
+fn bare_ipv6_address_is_taken_as_host() {
+    let address = "tcp6:::1".parse::<ServerAddress>().unwrap();
+
+    assert_eq!(address, tcp("::1", DEFAULT_PORT, AddressFamily::V6));
+}
+
+#[test]
+fn host_without_prefix_or_port() {
+    let address = "host".parse::<ServerAddress>().unwrap();
+
+    assert_eq!(address, tcp("host", DEFAULT_PORT, AddressFamily::Any));
+}
+
+#[test]
+fn host_with_port() {
+    let address = "host:1234".parse::<ServerAddress>().unwrap();
+
+    assert_eq!(address, tcp("host", 1234, AddressFamily::Any));
+}
+
+#[test]
+fn unix_socket_paths() {
+    assert_eq!("unix:/run/pulse/native".parse::<ServerAddress>().unwrap(), ServerAddress::unix("/run/pulse/native"));
+    assert_eq!("/run/pulse/native".parse::<ServerAddress>().unwrap(), ServerAddress::unix("/run/pulse/native"));
+}
+
+#[test]
+fn trailing_characters_after_ipv6_address_are_rejected() {
+    assert!("[::1]x".parse::<ServerAddress>().is_err());
+}
+
+#[test]
+fn relative_unix_socket_path_is_rejected() {
+    assert!("unix:relative".parse::<ServerAddress>().is_err());
+}
+
+#[test]
+fn invalid_port_is_rejected() {
+    assert!("host:port".parse::<ServerAddress>().is_err());
+}
+
+#[test]
+fn address_for_another_machine_is_rejected() {
+    assert!("{not-a-machine-id}unix:/run/pulse/native".parse::<ServerAddress>().is_err());
+}
+
+#[test]
+fn server_list_keeps_invalid_entries_as_errors() {
+    let addresses = parse_server_list("unix:relative  host:1234\t/run/pulse/native");
+
+    assert_eq!(addresses.len(), 3);
+    assert!(addresses[0].is_err());
+    assert_eq!(*addresses[1].as_ref().unwrap(), tcp("host", 1234, AddressFamily::Any));
+    assert_eq!(*addresses[2].as_ref().unwrap(), ServerAddress::unix("/run/pulse/native"));
+}
@@ -0,0 +1,138 @@
+use repulse::{command::{SinkInfo, DeviceState, PortAvailable}, format::Encoding, tag_struct::TagStruct, VOLUME_NORMAL};
+use std::time::Duration;
+
+/// `GetSinkInfo` reply of a sound card sink, with the protocol version each field was introduced in
+const ANALOG_SINK_REPLY: &[(u32, &[u8])] = &[
+    (0, b"L\x00\x00\x00\x01"), // index
+    (0, b"talsa_output.pci-0000_00_1f.3.analog-stereo\0"), // name
+    (0, b"tBuilt-in Audio Analog Stereo\0"), // description
+    (0, b"a\x03\x02\x00\x00\xbb\x80"), // sample spec: s16le, 2 channels, 48000 Hz
+    (0, b"m\x02\x01\x02"), // channel map: front left, front right
+    (0, b"L\x00\x00\x00\x07"), // owner module
+    (0, b"v\x02\x00\x01\x00\x00\x00\x01\x00\x00"), // volume
+    (0, b"0"), // mute
+    (0, b"L\x00\x00\x00\x02"), // monitor source
+    (0, b"talsa_output.pci-0000_00_1f.3.analog-stereo.monitor\0"), // monitor source name
+    (0, b"U\x00\x00\x00\x00\x00\x00\x07\xd0"), // latency
+    (0, b"tmodule-alsa-card.c\0"), // driver
+    (0, b"L\x00\x00\x00\x27"), // flags
+    (13, b"P"), // proplist
+    (13, b"tdevice.class\0"),
+    (13, b"L\x00\x00\x00\x06"),
+    (13, b"x\x00\x00\x00\x06sound\0"),
+    (13, b"N"),
+    (13, b"U\x00\x00\x00\x00\x00\x00\x4e\x20"), // configured latency
+    (15, b"V\x00\x01\x00\x00"), // base volume
+    (15, b"L\x00\x00\x00\x01"), // state: idle
+    (15, b"L\x00\x01\x00\x01"), // volume steps
+    (15, b"L\x00\x00\x00\x00"), // card
+    (16, b"L\x00\x00\x00\x01"), // port count
+    (16, b"tanalog-output-speaker\0"), // port name
+    (16, b"tSpeakers\0"), // port description
+    (16, b"L\x00\x00\x27\x10"), // port priority
+    (24, b"L\x00\x00\x00\x02"), // port available: yes
+    (34, b"tLegacy 1\0"), // port availability group
+    (34, b"L\x00\x00\x00\x04"), // port type
+    (16, b"tanalog-output-speaker\0"), // active port
+    (21, b"B\x01"), // format count
+    (21, b"fB\x01PN"), // format: pcm
+];
+
+/// Position of the monitor source index in `ANALOG_SINK_REPLY`
+const MONITOR_SOURCE_FIELD: usize = 8;
+
+fn reply_bytes(reply: &[(u32, &[u8])], version: u32) -> Vec<u8> {
+    reply.iter()
+        .filter(|(since, _)| version >= *since)
+        .flat_map(|(_, bytes)| bytes.iter().copied())
+        .collect()
+}
+
+fn pop_sink_info(bytes: &[u8], version: u32) -> SinkInfo {
+    let mut tag_struct = TagStruct::parse(bytes).unwrap();
+    tag_struct.set_protocol_version(version);
+
+    let sink = tag_struct.pop::<SinkInfo>().unwrap();
+    assert!(tag_struct.is_empty());
+
+    sink
+}
+
+#[test]
+fn sink_reply_is_decoded_at_v35() {
+    let sink = pop_sink_info(&reply_bytes(ANALOG_SINK_REPLY, 35), 35);
+
+    assert_eq!(sink.index, 1);
+    assert_eq!(sink.name, "alsa_output.pci-0000_00_1f.3.analog-stereo");
+    assert_eq!(sink.description.as_deref(), Some("Built-in Audio Analog Stereo"));
+    assert_eq!((sink.sample_spec.channels, sink.sample_spec.rate), (2, 48000));
+    assert_eq!(sink.owner_module, Some(7));
+    assert_eq!(sink.volume.volumes, [VOLUME_NORMAL, VOLUME_NORMAL]);
+    assert!(!sink.mute);
+    assert_eq!(sink.monitor_source, Some(2));
+    assert_eq!(sink.latency, Duration::from_micros(2000));
+    assert_eq!(sink.flags.0, 0x27);
+    assert_eq!(sink.proplist.unwrap().get_str("device.class"), Some("sound"));
+    assert_eq!(sink.configured_latency, Some(Duration::from_micros(20000)));
+    assert_eq!(sink.base_volume, Some(VOLUME_NORMAL));
+    assert_eq!(sink.state, Some(DeviceState::Idle));
+    assert_eq!(sink.n_volume_steps, Some(65537));
+    assert_eq!(sink.card, Some(0));
+    assert_eq!(sink.active_port.as_deref(), Some("analog-output-speaker"));
+
+    let port = &sink.ports[0];
+    assert_eq!(port.name, "analog-output-speaker");
+    assert_eq!(port.priority, 10000);
+    assert_eq!(port.available, Some(PortAvailable::Yes));
+    assert_eq!(port.availability_group.as_deref(), Some("Legacy 1"));
+    assert_eq!(port.port_type, Some(4));
+
+    assert_eq!(sink.formats.len(), 1);
+    assert_eq!(sink.formats[0].encoding, Encoding::PCM);
+}
+
+#[test]
+fn port_fields_are_gated_by_version() {
+    let sink = pop_sink_info(&reply_bytes(ANALOG_SINK_REPLY, 23), 23);
+
+    let port = &sink.ports[0];
+    assert_eq!(port.name, "analog-output-speaker");
+    assert_eq!(port.available, None);
+    assert_eq!(port.availability_group, None);
+    assert_eq!(port.port_type, None);
+    assert_eq!(sink.formats.len(), 1);
+}
+
+#[test]
+fn sink_reply_is_decoded_at_v15() {
+    let sink = pop_sink_info(&reply_bytes(ANALOG_SINK_REPLY, 15), 15);
+
+    assert_eq!(sink.monitor_source, Some(2));
+    assert!(sink.proplist.is_some());
+    assert_eq!(sink.state, Some(DeviceState::Idle));
+    assert_eq!(sink.card, Some(0));
+    assert!(sink.ports.is_empty());
+    assert_eq!(sink.active_port, None);
+    assert!(sink.formats.is_empty());
+}
+
+#[test]
+fn sink_reply_is_decoded_at_v12() {
+    let sink = pop_sink_info(&reply_bytes(ANALOG_SINK_REPLY, 12), 12);
+
+    assert_eq!(sink.proplist, None);
+    assert_eq!(sink.configured_latency, None);
+    assert_eq!(sink.base_volume, None);
+    assert_eq!(sink.state, None);
+    assert_eq!(sink.card, None);
+}
+
+#[test]
+fn invalid_monitor_source_is_decoded_as_none() {
+    let mut reply = ANALOG_SINK_REPLY.to_vec();
+    reply[MONITOR_SOURCE_FIELD].1 = b"L\xff\xff\xff\xff";
+
+    let sink = pop_sink_info(&reply_bytes(&reply, 35), 35);
+
+    assert_eq!(sink.monitor_source, None);
+}
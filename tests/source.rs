@@ -0,0 +1,104 @@
+use repulse::{command::{SourceInfo, DeviceState, PortAvailable}, format::Encoding, tag_struct::TagStruct, VOLUME_NORMAL};
+use std::time::Duration;
+
+/// `GetSourceInfo` reply of a sink monitor, with the protocol version each field was introduced in
+const MONITOR_SOURCE_REPLY: &[(u32, &[u8])] = &[
+    (0, b"L\x00\x00\x00\x02"), // index
+    (0, b"talsa_output.pci-0000_00_1f.3.analog-stereo.monitor\0"), // name
+    (0, b"tMonitor of Built-in Audio Analog Stereo\0"), // description
+    (0, b"a\x03\x02\x00\x00\xbb\x80"), // sample spec: s16le, 2 channels, 48000 Hz
+    (0, b"m\x02\x01\x02"), // channel map: front left, front right
+    (0, b"L\x00\x00\x00\x07"), // owner module
+    (0, b"v\x02\x00\x01\x00\x00\x00\x01\x00\x00"), // volume
+    (0, b"0"), // mute
+    (0, b"L\x00\x00\x00\x01"), // monitor of sink
+    (0, b"talsa_output.pci-0000_00_1f.3.analog-stereo\0"), // monitor of sink name
+    (0, b"U\x00\x00\x00\x00\x00\x00\x00\x00"), // latency
+    (0, b"tmodule-alsa-card.c\0"), // driver
+    (0, b"L\x00\x00\x00\x22"), // flags
+    (13, b"P"), // proplist
+    (13, b"tdevice.class\0"),
+    (13, b"L\x00\x00\x00\x08"),
+    (13, b"x\x00\x00\x00\x08monitor\0"),
+    (13, b"N"),
+    (13, b"U\x00\x00\x00\x00\x00\x00\x4e\x20"), // configured latency
+    (15, b"V\x00\x01\x00\x00"), // base volume
+    (15, b"L\x00\x00\x00\x02"), // state: suspended
+    (15, b"L\x00\x01\x00\x01"), // volume steps
+    (15, b"L\xff\xff\xff\xff"), // card: none
+    (16, b"L\x00\x00\x00\x01"), // port count
+    (16, b"tanalog-output-speaker\0"), // port name
+    (16, b"tSpeakers\0"), // port description
+    (16, b"L\x00\x00\x27\x10"), // port priority
+    (24, b"L\x00\x00\x00\x00"), // port available: unknown
+    (34, b"N"), // port availability group
+    (34, b"L\x00\x00\x00\x00"), // port type
+    (16, b"tanalog-output-speaker\0"), // active port
+    (22, b"B\x01"), // format count
+    (22, b"fB\x01PN"), // format: pcm
+];
+
+fn reply_bytes(reply: &[(u32, &[u8])], version: u32) -> Vec<u8> {
+    reply.iter()
+        .filter(|(since, _)| version >= *since)
+        .flat_map(|(_, bytes)| bytes.iter().copied())
+        .collect()
+}
+
+fn pop_source_info(version: u32) -> SourceInfo {
+    let bytes = reply_bytes(MONITOR_SOURCE_REPLY, version);
+    let mut tag_struct = TagStruct::parse(&bytes).unwrap();
+    tag_struct.set_protocol_version(version);
+
+    let source = tag_struct.pop::<SourceInfo>().unwrap();
+    assert!(tag_struct.is_empty());
+
+    source
+}
+
+#[test]
+fn source_reply_is_decoded_at_v35() {
+    let source = pop_source_info(35);
+
+    assert_eq!(source.index, 2);
+    assert_eq!(source.name, "alsa_output.pci-0000_00_1f.3.analog-stereo.monitor");
+    assert!(source.is_monitor());
+    assert_eq!(source.monitor_of_sink, Some(1));
+    assert_eq!(source.monitor_of_sink_name.as_deref(), Some("alsa_output.pci-0000_00_1f.3.analog-stereo"));
+    assert_eq!(source.volume.volumes, [VOLUME_NORMAL, VOLUME_NORMAL]);
+    assert_eq!(source.flags.0, 0x22);
+    assert_eq!(source.proplist.unwrap().get_str("device.class"), Some("monitor"));
+    assert_eq!(source.configured_latency, Some(Duration::from_micros(20000)));
+    assert_eq!(source.state, Some(DeviceState::Suspended));
+    assert_eq!(source.card, None);
+    assert_eq!(source.active_port.as_deref(), Some("analog-output-speaker"));
+
+    let port = &source.ports[0];
+    assert_eq!(port.available, Some(PortAvailable::Unknown));
+    assert_eq!(port.availability_group, None);
+    assert_eq!(port.port_type, Some(0));
+
+    assert_eq!(source.formats.len(), 1);
+    assert_eq!(source.formats[0].encoding, Encoding::PCM);
+}
+
+#[test]
+fn formats_are_only_decoded_since_v22() {
+    // Sinks send their formats since v21, sources only since v22
+    let source = pop_source_info(21);
+
+    assert_eq!(source.ports.len(), 1);
+    assert!(source.formats.is_empty());
+}
+
+#[test]
+fn source_reply_is_decoded_at_v15() {
+    let source = pop_source_info(15);
+
+    assert_eq!(source.monitor_of_sink, Some(1));
+    assert!(source.proplist.is_some());
+    assert_eq!(source.state, Some(DeviceState::Suspended));
+    assert!(source.ports.is_empty());
+    assert_eq!(source.active_port, None);
+    assert!(source.formats.is_empty());
+}
@@ -0,0 +1,151 @@
+use repulse::{command::{SinkInputInfo, SourceOutputInfo}, format::Encoding, tag_struct::TagStruct, VOLUME_NORMAL};
+use std::time::Duration;
+
+/// `GetSinkInputInfo` reply of a music player, with the protocol version each field was introduced in
+const PLAYER_SINK_INPUT_REPLY: &[(u32, &[u8])] = &[
+    (0, b"L\x00\x00\x00\x05"), // index
+    (0, b"tPlayback\0"), // name
+    (0, b"L\xff\xff\xff\xff"), // owner module: none
+    (0, b"L\x00\x00\x00\x09"), // client
+    (0, b"L\x00\x00\x00\x01"), // sink
+    (0, b"a\x03\x02\x00\x00\xac\x44"), // sample spec: s16le, 2 channels, 44100 Hz
+    (0, b"m\x02\x01\x02"), // channel map: front left, front right
+    (0, b"v\x02\x00\x01\x00\x00\x00\x01\x00\x00"), // volume
+    (0, b"U\x00\x00\x00\x00\x00\x00\x27\x10"), // buffer latency
+    (0, b"U\x00\x00\x00\x00\x00\x00\x07\xd0"), // sink latency
+    (0, b"tspeex-float-1\0"), // resample method
+    (0, b"tprotocol-native.c\0"), // driver
+    (11, b"0"), // mute
+    (13, b"P"), // proplist
+    (13, b"tapplication.name\0"),
+    (13, b"L\x00\x00\x00\x06"),
+    (13, b"x\x00\x00\x00\x06Music\0"),
+    (13, b"N"),
+    (19, b"1"), // corked
+    (20, b"1"), // has volume
+    (20, b"1"), // volume writable
+    (21, b"fB\x01PN"), // format: pcm
+];
+
+/// `GetSourceOutputInfo` reply of a voice recorder, with the protocol version each field was introduced in
+const RECORDER_SOURCE_OUTPUT_REPLY: &[(u32, &[u8])] = &[
+    (0, b"L\x00\x00\x00\x06"), // index
+    (0, b"tRecord Stream\0"), // name
+    (0, b"L\xff\xff\xff\xff"), // owner module: none
+    (0, b"L\x00\x00\x00\x0a"), // client
+    (0, b"L\x00\x00\x00\x03"), // source
+    (0, b"a\x03\x01\x00\x00\x3e\x80"), // sample spec: s16le, 1 channel, 16000 Hz
+    (0, b"m\x01\x00"), // channel map: mono
+    (0, b"U\x00\x00\x00\x00\x00\x00\x27\x10"), // buffer latency
+    (0, b"U\x00\x00\x00\x00\x00\x00\x03\xe8"), // source latency
+    (0, b"N"), // resample method: none
+    (0, b"tprotocol-native.c\0"), // driver
+    (13, b"PN"), // proplist
+    (19, b"0"), // corked
+    (22, b"v\x01\x00\x01\x00\x00"), // volume
+    (22, b"1"), // mute
+    (22, b"1"), // has volume
+    (22, b"0"), // volume writable
+    (22, b"fB\x01PN"), // format: pcm
+];
+
+fn reply_bytes(reply: &[(u32, &[u8])], version: u32) -> Vec<u8> {
+    reply.iter()
+        .filter(|(since, _)| version >= *since)
+        .flat_map(|(_, bytes)| bytes.iter().copied())
+        .collect()
+}
+
+fn pop_sink_input_info(version: u32) -> SinkInputInfo {
+    let bytes = reply_bytes(PLAYER_SINK_INPUT_REPLY, version);
+    let mut tag_struct = TagStruct::parse(&bytes).unwrap();
+    tag_struct.set_protocol_version(version);
+
+    let sink_input = tag_struct.pop::<SinkInputInfo>().unwrap();
+    assert!(tag_struct.is_empty());
+
+    sink_input
+}
+
+fn pop_source_output_info(version: u32) -> SourceOutputInfo {
+    let bytes = reply_bytes(RECORDER_SOURCE_OUTPUT_REPLY, version);
+    let mut tag_struct = TagStruct::parse(&bytes).unwrap();
+    tag_struct.set_protocol_version(version);
+
+    let source_output = tag_struct.pop::<SourceOutputInfo>().unwrap();
+    assert!(tag_struct.is_empty());
+
+    source_output
+}
+
+#[test]
+fn sink_input_reply_is_decoded_at_v35() {
+    let sink_input = pop_sink_input_info(35);
+
+    assert_eq!(sink_input.index, 5);
+    assert_eq!(sink_input.name.as_deref(), Some("Playback"));
+    assert_eq!(sink_input.owner_module, None);
+    assert_eq!(sink_input.client, Some(9));
+    assert_eq!(sink_input.sink, 1);
+    assert_eq!(sink_input.volume.volumes, [VOLUME_NORMAL, VOLUME_NORMAL]);
+    assert_eq!(sink_input.buffer_latency, Duration::from_micros(10000));
+    assert_eq!(sink_input.sink_latency, Duration::from_micros(2000));
+    assert_eq!(sink_input.resample_method.as_deref(), Some("speex-float-1"));
+    assert_eq!(sink_input.mute, Some(false));
+    assert_eq!(sink_input.proplist.unwrap().get_str("application.name"), Some("Music"));
+    assert_eq!(sink_input.corked, Some(true));
+    assert_eq!(sink_input.has_volume, Some(true));
+    assert_eq!(sink_input.volume_writable, Some(true));
+    assert_eq!(sink_input.format.unwrap().encoding, Encoding::PCM);
+}
+
+#[test]
+fn sink_input_fields_are_gated_by_version() {
+    let v20 = pop_sink_input_info(20);
+    assert_eq!(v20.has_volume, Some(true));
+    assert_eq!(v20.format, None);
+
+    let v19 = pop_sink_input_info(19);
+    assert_eq!(v19.corked, Some(true));
+    assert_eq!(v19.has_volume, None);
+    assert_eq!(v19.volume_writable, None);
+
+    let v15 = pop_sink_input_info(15);
+    assert_eq!(v15.mute, Some(false));
+    assert!(v15.proplist.is_some());
+    assert_eq!(v15.corked, None);
+}
+
+#[test]
+fn source_output_reply_is_decoded_at_v35() {
+    let source_output = pop_source_output_info(35);
+
+    assert_eq!(source_output.index, 6);
+    assert_eq!(source_output.name.as_deref(), Some("Record Stream"));
+    assert_eq!(source_output.client, Some(10));
+    assert_eq!(source_output.source, 3);
+    assert_eq!((source_output.sample_spec.channels, source_output.sample_spec.rate), (1, 16000));
+    assert_eq!(source_output.source_latency, Duration::from_micros(1000));
+    assert_eq!(source_output.resample_method, None);
+    assert_eq!(source_output.corked, Some(false));
+    assert_eq!(source_output.volume.unwrap().volumes, [VOLUME_NORMAL]);
+    assert_eq!(source_output.mute, Some(true));
+    assert_eq!(source_output.has_volume, Some(true));
+    assert_eq!(source_output.volume_writable, Some(false));
+    assert_eq!(source_output.format.unwrap().encoding, Encoding::PCM);
+}
+
+#[test]
+fn source_output_fields_are_gated_by_version() {
+    let v21 = pop_source_output_info(21);
+    assert_eq!(v21.corked, Some(false));
+    assert_eq!(v21.volume, None);
+    assert_eq!(v21.mute, None);
+    assert_eq!(v21.has_volume, None);
+    assert_eq!(v21.volume_writable, None);
+    assert_eq!(v21.format, None);
+
+    let v15 = pop_source_output_info(15);
+    assert!(v15.proplist.is_some());
+    assert_eq!(v15.corked, None);
+}
@@ -0,0 +1,117 @@
+use repulse::{tag_struct::{TagStruct, ChannelMap, ChannelVolume}, channel::{ChannelPosition, CHANNELS_MAX}, VOLUME_MAX};
+use std::time::{Duration, UNIX_EPOCH};
+
+fn encode(put: impl FnOnce(&mut TagStruct)) -> Vec<u8> {
+    let mut tag_struct = TagStruct::new();
+    put(&mut tag_struct);
+
+    tag_struct.to_bytes().unwrap().to_vec()
+}
+
+#[test]
+fn u64_round_trips() {
+    let bytes = encode(|tag_struct| tag_struct.put_u64(0x0102_0304_0506_0708));
+
+    assert_eq!(bytes, b"R\x01\x02\x03\x04\x05\x06\x07\x08");
+    assert_eq!(TagStruct::parse(&bytes).unwrap().pop_u64().unwrap(), 0x0102_0304_0506_0708);
+}
+
+#[test]
+fn s64_round_trips() {
+    let bytes = encode(|tag_struct| tag_struct.put_s64(-2));
+
+    assert_eq!(bytes, b"r\xff\xff\xff\xff\xff\xff\xff\xfe");
+    assert_eq!(TagStruct::parse(&bytes).unwrap().pop_s64().unwrap(), -2);
+}
+
+#[test]
+fn usec_round_trips() {
+    let bytes = encode(|tag_struct| tag_struct.put_usec(Duration::from_micros(0x0102_0304)));
+
+    assert_eq!(bytes, b"U\x00\x00\x00\x00\x01\x02\x03\x04");
+    assert_eq!(TagStruct::parse(&bytes).unwrap().pop_usec().unwrap(), Duration::from_micros(0x0102_0304));
+}
+
+#[test]
+fn usec_saturates() {
+    let bytes = encode(|tag_struct| tag_struct.put_usec(Duration::from_secs(u64::MAX)));
+
+    assert_eq!(bytes, b"U\xff\xff\xff\xff\xff\xff\xff\xff");
+    assert_eq!(TagStruct::parse(&bytes).unwrap().pop_usec().unwrap(), Duration::from_micros(u64::MAX));
+}
+
+#[test]
+fn timeval_is_split_into_seconds_and_microseconds() {
+    let time = UNIX_EPOCH + Duration::new(0x0102_0304, 5_000_999);
+    let bytes = encode(|tag_struct| tag_struct.put_timeval(time));
+
+    // Sub-microsecond precision is truncated
+    assert_eq!(bytes, b"T\x01\x02\x03\x04\x00\x00\x13\x88");
+    assert_eq!(
+        TagStruct::parse(&bytes).unwrap().pop_timeval().unwrap(),
+        UNIX_EPOCH + Duration::new(0x0102_0304, 5_000_000),
+    );
+}
+
+#[test]
+fn timeval_before_epoch_is_rejected() {
+    let mut tag_struct = TagStruct::new();
+    tag_struct.put_timeval(UNIX_EPOCH - Duration::from_secs(1));
+
+    assert!(tag_struct.to_bytes().is_err());
+}
+
+#[test]
+fn wrong_tag_is_rejected() {
+    let bytes = encode(|tag_struct| tag_struct.put_u64(1));
+
+    assert!(TagStruct::parse(&bytes).unwrap().pop_s64().is_err());
+}
+
+#[test]
+fn channel_map_and_volume_round_trip() {
+    let bytes = encode(|tag_struct| {
+        tag_struct.put_channel_map(ChannelMap::stereo());
+        tag_struct.put_channel_volume(ChannelVolume::uniform(2, VOLUME_MAX));
+    });
+
+    assert_eq!(bytes, b"m\x02\x01\x02v\x02\x7f\xff\xff\xff\x7f\xff\xff\xff");
+
+    let mut tag_struct = TagStruct::parse(&bytes).unwrap();
+    let channel_map = tag_struct.pop_channel_map().unwrap();
+    let channel_volume = tag_struct.pop_channel_volume().unwrap();
+
+    assert!(matches!(channel_map.positions[..], [ChannelPosition::FrontLeft, ChannelPosition::FrontRight]));
+    assert_eq!(channel_volume, ChannelVolume::uniform(2, VOLUME_MAX));
+}
+
+#[test]
+fn channel_map_with_too_many_channels_is_rejected() {
+    let mut bytes = vec![b'm', CHANNELS_MAX as u8 + 1];
+    bytes.resize(bytes.len() + CHANNELS_MAX + 1, 1);
+
+    assert!(TagStruct::parse(&bytes).is_err());
+}
+
+#[test]
+fn channel_map_with_unknown_position_is_rejected() {
+    assert!(TagStruct::parse(b"m\x02\x01\x64").is_err());
+}
+
+#[test]
+fn channel_map_with_invalid_position_is_rejected() {
+    assert!(TagStruct::parse(b"m\x02\x01\xff").is_err());
+}
+
+#[test]
+fn channel_volume_with_too_many_channels_is_rejected() {
+    let mut bytes = vec![b'v', CHANNELS_MAX as u8 + 1];
+    bytes.resize(bytes.len() + (CHANNELS_MAX + 1) * 4, 0);
+
+    assert!(TagStruct::parse(&bytes).is_err());
+}
+
+#[test]
+fn channel_volume_above_maximum_is_rejected() {
+    assert!(TagStruct::parse(b"v\x01\x80\x00\x00\x00").is_err());
+}